@@ -0,0 +1,75 @@
+use super::PciCapabilityAddress;
+use crate::ConfigRegionAccess;
+use bit_field::BitField;
+
+bitflags::bitflags! {
+    /// The Access Control Services features of a function. This is the layout of both the ACS Capability
+    /// register, which reports which features are implemented, and the ACS Control register, which enables
+    /// them.
+    #[repr(transparent)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct AcsFlags: u16 {
+        const SOURCE_VALIDATION = 1 << 0;
+        const TRANSLATION_BLOCKING = 1 << 1;
+        const P2P_REQUEST_REDIRECT = 1 << 2;
+        const P2P_COMPLETION_REDIRECT = 1 << 3;
+        const UPSTREAM_FORWARDING = 1 << 4;
+        const P2P_EGRESS_CONTROL = 1 << 5;
+        const DIRECT_TRANSLATED_P2P = 1 << 6;
+        const _ = !0;
+    }
+}
+
+/// The Access Control Services (ACS) extended capability, which controls whether peer-to-peer requests
+/// can be routed directly between functions, or must be sent upstream (e.g. to be checked by an IOMMU).
+#[derive(Clone, Copy, Debug)]
+pub struct AcsCapability {
+    pub(super) address: PciCapabilityAddress,
+}
+
+impl AcsCapability {
+    pub(crate) fn new(address: PciCapabilityAddress) -> AcsCapability {
+        AcsCapability { address }
+    }
+
+    /// The ACS features implemented by the function
+    pub fn capabilities(&self, access: impl ConfigRegionAccess) -> AcsFlags {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        // Bits `8..16` hold the Egress Control Vector Size, rather than flags
+        AcsFlags::from_bits_retain(reg.get_bits(0..8) as u16)
+    }
+
+    /// The number of bits in the Egress Control Vector. Only meaningful if the function implements
+    /// [`AcsFlags::P2P_EGRESS_CONTROL`].
+    pub fn egress_control_vector_size(&self, access: impl ConfigRegionAccess) -> u16 {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        match reg.get_bits(8..16) {
+            0 => 256,
+            size => size as u16,
+        }
+    }
+
+    /// The ACS features currently enabled
+    pub fn control(&self, access: impl ConfigRegionAccess) -> AcsFlags {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        AcsFlags::from_bits_retain(reg.get_bits(16..32) as u16)
+    }
+
+    /// Write the ACS Control register, including any bits not named by [`AcsFlags`]. Features the function
+    /// does not implement are ignored.
+    pub fn set_control(&self, control: AcsFlags, access: impl ConfigRegionAccess) {
+        let mut reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        reg.set_bits(16..32, control.bits() as u32);
+        unsafe {
+            access.write(self.address.address, self.address.offset + 0x04, reg);
+        }
+    }
+
+    /// Enable or disable the ACS features in `features`, leaving the other bits of the ACS Control register
+    /// unchanged.
+    pub fn set_enabled(&self, features: AcsFlags, enabled: bool, access: impl ConfigRegionAccess) {
+        let mut control = self.control(&access);
+        control.set(features, enabled);
+        self.set_control(control, access);
+    }
+}
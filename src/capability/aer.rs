@@ -0,0 +1,219 @@
+use super::{DevicePortType, PciCapabilityAddress, RegisterNotPresent};
+use crate::ConfigRegionAccess;
+use bit_field::BitField;
+
+/// The kind of a TLP (Transaction Layer Packet), decoded from the Fmt and Type fields of its header.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TlpKind {
+    MemoryRead,
+    MemoryReadLocked,
+    MemoryWrite,
+    IoRead,
+    IoWrite,
+    ConfigRead { type1: bool },
+    ConfigWrite { type1: bool },
+    Message,
+    Completion { with_data: bool },
+    Other { fmt: u8, tlp_type: u8 },
+}
+
+/// The header of a TLP, as captured in the Header Log of the AER capability.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TlpHeader(pub [u32; 4]);
+
+impl TlpHeader {
+    /// The Fmt field, which encodes the length of the header and whether the TLP has a data payload
+    pub fn fmt(&self) -> u8 {
+        self.0[0].get_bits(29..32) as u8
+    }
+
+    /// The Type field
+    pub fn tlp_type(&self) -> u8 {
+        self.0[0].get_bits(24..29) as u8
+    }
+
+    pub fn kind(&self) -> TlpKind {
+        let fmt = self.fmt();
+        let tlp_type = self.tlp_type();
+        let with_data = fmt.get_bit(1);
+        match tlp_type {
+            0b00000 if with_data => TlpKind::MemoryWrite,
+            0b00000 => TlpKind::MemoryRead,
+            0b00001 if !with_data => TlpKind::MemoryReadLocked,
+            0b00010 if with_data => TlpKind::IoWrite,
+            0b00010 => TlpKind::IoRead,
+            0b00100 | 0b00101 if with_data => TlpKind::ConfigWrite { type1: tlp_type == 0b00101 },
+            0b00100 | 0b00101 => TlpKind::ConfigRead { type1: tlp_type == 0b00101 },
+            0b10000..=0b10111 => TlpKind::Message,
+            0b01010 => TlpKind::Completion { with_data },
+            _ => TlpKind::Other { fmt, tlp_type },
+        }
+    }
+
+    /// The Requester ID of the TLP, in the form of a bus, device, and function number packed into 16 bits.
+    /// Returns `None` for completions, which carry a Completer ID in this position instead.
+    pub fn requester_id(&self) -> Option<u16> {
+        match self.kind() {
+            TlpKind::Completion { .. } => None,
+            _ => Some(self.0[1].get_bits(16..32) as u16),
+        }
+    }
+}
+
+/// The Advanced Error Reporting extended capability, which reports errors in more detail than the
+/// PCI Express capability.
+#[derive(Clone, Copy, Debug)]
+pub struct AerCapability {
+    pub(super) address: PciCapabilityAddress,
+}
+
+impl AerCapability {
+    pub(crate) fn new(address: PciCapabilityAddress) -> AerCapability {
+        AerCapability { address }
+    }
+
+    /// The Header Log, which holds the header of the TLP associated with the first uncorrectable error
+    /// recorded. Only valid while the corresponding bit of the Uncorrectable Error Status register is set.
+    pub fn header_log(&self, access: impl ConfigRegionAccess) -> [u32; 4] {
+        let mut log = [0; 4];
+        for (i, dword) in log.iter_mut().enumerate() {
+            *dword = unsafe { access.read(self.address.address, self.address.offset + 0x1c + i as u16 * 4) };
+        }
+        log
+    }
+
+    /// The Header Log, decoded as a TLP header. See [`AerCapability::header_log`].
+    pub fn logged_tlp_header(&self, access: impl ConfigRegionAccess) -> TlpHeader {
+        TlpHeader(self.header_log(access))
+    }
+
+    /// Read the Uncorrectable Error Severity register. Errors whose bits are set are reported as fatal, and
+    /// the others as non-fatal.
+    pub fn uncorrectable_severity(&self, access: impl ConfigRegionAccess) -> UncorrectableErrors {
+        UncorrectableErrors::from_bits_retain(unsafe {
+            access.read(self.address.address, self.address.offset + 0x0c)
+        })
+    }
+
+    /// Write the Uncorrectable Error Severity register, to choose which uncorrectable errors are reported
+    /// as fatal (bits set in `severity`) or non-fatal (bits clear). Bits for errors the function does not
+    /// implement are ignored.
+    pub fn set_uncorrectable_severity(&self, severity: UncorrectableErrors, access: impl ConfigRegionAccess) {
+        unsafe {
+            access.write(self.address.address, self.address.offset + 0x0c, severity.bits());
+        }
+    }
+
+    /// Whether a function of this type implements the Root Error registers.
+    fn has_root_registers(port_type: DevicePortType) -> bool {
+        matches!(port_type, DevicePortType::RootPort | DevicePortType::RootComplexEventCollector)
+    }
+
+    /// Read the Root Error Status register. `port_type` is the type of the function, as reported by
+    /// [`PciExpressCapability::device_port_type`](super::PciExpressCapability::device_port_type). Returns
+    /// `None` if it is not a Root Port or Root Complex Event Collector, which are the only functions that
+    /// implement this register.
+    pub fn root_error_status(
+        &self,
+        port_type: DevicePortType,
+        access: impl ConfigRegionAccess,
+    ) -> Option<RootErrorStatus> {
+        if !Self::has_root_registers(port_type) {
+            return None;
+        }
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x30) };
+        Some(RootErrorStatus::from_bits_retain(reg))
+    }
+
+    /// Acknowledge the errors in `mask` by writing `1` to them in the Root Error Status register.
+    pub fn clear_root_error_status(
+        &self,
+        mask: RootErrorStatus,
+        port_type: DevicePortType,
+        access: impl ConfigRegionAccess,
+    ) -> Result<(), RegisterNotPresent> {
+        if !Self::has_root_registers(port_type) {
+            return Err(RegisterNotPresent);
+        }
+        unsafe {
+            access.write(self.address.address, self.address.offset + 0x30, mask.bits());
+        }
+        Ok(())
+    }
+
+    /// Read the Error Source Identification register, which holds the requester IDs of the functions that
+    /// sent the most recently recorded error messages. Returns `None` if the function is not a Root Port or
+    /// Root Complex Event Collector.
+    pub fn error_source_id(
+        &self,
+        port_type: DevicePortType,
+        access: impl ConfigRegionAccess,
+    ) -> Option<ErrorSourceId> {
+        if !Self::has_root_registers(port_type) {
+            return None;
+        }
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x34) };
+        Some(ErrorSourceId { correctable: reg.get_bits(0..16) as u16, uncorrectable: reg.get_bits(16..32) as u16 })
+    }
+}
+
+bitflags::bitflags! {
+    /// The uncorrectable errors reported by the AER capability. This is the layout of the Uncorrectable
+    /// Error Status, Mask, and Severity registers.
+    #[repr(transparent)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct UncorrectableErrors: u32 {
+        const DATA_LINK_PROTOCOL_ERROR = 1 << 4;
+        const SURPRISE_DOWN_ERROR = 1 << 5;
+        const POISONED_TLP_RECEIVED = 1 << 12;
+        const FLOW_CONTROL_PROTOCOL_ERROR = 1 << 13;
+        const COMPLETION_TIMEOUT = 1 << 14;
+        const COMPLETER_ABORT = 1 << 15;
+        const UNEXPECTED_COMPLETION = 1 << 16;
+        const RECEIVER_OVERFLOW = 1 << 17;
+        const MALFORMED_TLP = 1 << 18;
+        const ECRC_ERROR = 1 << 19;
+        const UNSUPPORTED_REQUEST_ERROR = 1 << 20;
+        const ACS_VIOLATION = 1 << 21;
+        const UNCORRECTABLE_INTERNAL_ERROR = 1 << 22;
+        const MC_BLOCKED_TLP = 1 << 23;
+        const ATOMIC_OP_EGRESS_BLOCKED = 1 << 24;
+        const TLP_PREFIX_BLOCKED_ERROR = 1 << 25;
+        const POISONED_TLP_EGRESS_BLOCKED = 1 << 26;
+        const _ = !0;
+    }
+}
+
+bitflags::bitflags! {
+    /// The Root Error Status register of a Root Port or Root Complex Event Collector. The error bits are
+    /// write-1-to-clear, and can be acknowledged with [`AerCapability::clear_root_error_status`].
+    #[repr(transparent)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct RootErrorStatus: u32 {
+        const ERR_COR_RECEIVED = 1 << 0;
+        const MULTIPLE_ERR_COR_RECEIVED = 1 << 1;
+        const ERR_FATAL_NONFATAL_RECEIVED = 1 << 2;
+        const MULTIPLE_ERR_FATAL_NONFATAL_RECEIVED = 1 << 3;
+        const FIRST_UNCORRECTABLE_FATAL = 1 << 4;
+        const NON_FATAL_ERROR_MESSAGES_RECEIVED = 1 << 5;
+        const FATAL_ERROR_MESSAGES_RECEIVED = 1 << 6;
+        const _ = !0;
+    }
+}
+
+impl RootErrorStatus {
+    /// The MSI or MSI-X vector used for Advanced Error Reporting interrupts
+    pub fn interrupt_message_number(&self) -> u8 {
+        self.bits().get_bits(27..32) as u8
+    }
+}
+
+/// The requester IDs recorded in the Error Source Identification register. Each is only valid while the
+/// corresponding bit of the Root Error Status register is set.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ErrorSourceId {
+    /// The requester ID of the function that sent the last recorded ERR_COR message
+    pub correctable: u16,
+    /// The requester ID of the function that sent the last recorded ERR_FATAL or ERR_NONFATAL message
+    pub uncorrectable: u16,
+}
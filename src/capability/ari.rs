@@ -0,0 +1,44 @@
+use super::PciCapabilityAddress;
+use crate::ConfigRegionAccess;
+use bit_field::BitField;
+
+/// The Alternative Routing-ID Interpretation (ARI) extended capability. Functions of an ARI device use the
+/// whole 8 bits of the device and function number as a function number, so a device can have up to 256
+/// functions. These are discovered by following the Next Function Number of each function, starting at
+/// function 0.
+#[derive(Clone, Copy, Debug)]
+pub struct AriCapability {
+    pub(super) address: PciCapabilityAddress,
+}
+
+impl AriCapability {
+    pub(crate) fn new(address: PciCapabilityAddress) -> AriCapability {
+        AriCapability { address }
+    }
+
+    /// The function number of the next function of the device, or `0` if this is the last function
+    pub fn next_function(&self, access: impl ConfigRegionAccess) -> u8 {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        reg.get_bits(8..16) as u8
+    }
+
+    /// Whether the function supports assigning functions to Function Groups for MFVC (Multi-Function
+    /// Virtual Channel) arbitration. Only implemented by function 0.
+    pub fn mfvc_function_groups_capable(&self, access: impl ConfigRegionAccess) -> bool {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        reg.get_bit(0)
+    }
+
+    /// Whether the function supports assigning functions to Function Groups for ACS (Access Control
+    /// Services) P2P egress control. Only implemented by function 0.
+    pub fn acs_function_groups_capable(&self, access: impl ConfigRegionAccess) -> bool {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        reg.get_bit(1)
+    }
+
+    /// The Function Group the function is assigned to
+    pub fn function_group(&self, access: impl ConfigRegionAccess) -> u8 {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        reg.get_bits(20..23) as u8
+    }
+}
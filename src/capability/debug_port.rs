@@ -0,0 +1,33 @@
+use super::PciCapabilityAddress;
+use bit_field::BitField;
+
+/// The Debug Port capability, which locates the registers of a USB debug port (as implemented by EHCI
+/// controllers) within one of the function's BARs.
+#[derive(Clone, Copy, Debug)]
+pub struct DebugPortCapability {
+    pub(super) address: PciCapabilityAddress,
+    control: u16,
+}
+
+impl DebugPortCapability {
+    pub(crate) fn new(address: PciCapabilityAddress, control: u16) -> DebugPortCapability {
+        DebugPortCapability { address, control }
+    }
+
+    /// The slot of the BAR that contains the debug port's registers. Returns `None` if the capability
+    /// reports an invalid BAR.
+    pub fn bar_index(&self) -> Option<u8> {
+        /*
+         * The BAR is encoded as a number from 1 (the BAR at `0x10`) to 6 (the BAR at `0x24`).
+         */
+        match self.control.get_bits(13..16) as u8 {
+            bar @ 1..=6 => Some(bar - 1),
+            _ => None,
+        }
+    }
+
+    /// The offset of the debug port's registers within their BAR
+    pub fn offset(&self) -> u16 {
+        self.control.get_bits(0..13)
+    }
+}
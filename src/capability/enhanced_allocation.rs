@@ -0,0 +1,114 @@
+use super::PciCapabilityAddress;
+use crate::{ConfigRegionAccess, PciAddress};
+use bit_field::BitField;
+
+/// The Enhanced Allocation capability, used by functions with fixed resources instead of (or as well
+/// as) sizable BARs.
+#[derive(Clone, Copy, Debug)]
+pub struct EnhancedAllocationCapability {
+    pub(super) address: PciCapabilityAddress,
+    num_entries: u8,
+    /// Offset of the first entry from the start of the capability. Type-1 headers have an extra dword
+    /// for fixed bus numbers before the entries.
+    entries_offset: u16,
+}
+
+impl EnhancedAllocationCapability {
+    pub(crate) fn new(
+        address: PciCapabilityAddress,
+        control: u16,
+        access: impl ConfigRegionAccess,
+    ) -> EnhancedAllocationCapability {
+        let header_type = unsafe { access.read(address.address, 0x0c) }.get_bits(16..23);
+        EnhancedAllocationCapability {
+            address,
+            num_entries: control.get_bits(0..6) as u8,
+            entries_offset: if header_type == 0x01 { 0x08 } else { 0x04 },
+        }
+    }
+
+    /// The number of entries in the capability
+    pub fn num_entries(&self) -> u8 {
+        self.num_entries
+    }
+
+    /// Iterate over the entries of the capability
+    pub fn entries<T: ConfigRegionAccess>(&self, access: T) -> EnhancedAllocationEntryIterator<T> {
+        EnhancedAllocationEntryIterator {
+            address: self.address.address,
+            offset: self.address.offset + self.entries_offset,
+            remaining: self.num_entries,
+            access,
+        }
+    }
+}
+
+/// A resource range described by the Enhanced Allocation capability.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct EnhancedAllocationEntry {
+    /// The BAR Equivalent Indicator. Values `0` to `5` mean the entry is equivalent to the BAR in that
+    /// slot; the meaning of other values is defined by the specification.
+    pub bar_equivalent_indicator: u8,
+    pub primary_properties: u8,
+    pub secondary_properties: u8,
+    pub writable: bool,
+    pub enabled: bool,
+    /// The address of the start of the resource range
+    pub base: u64,
+    /// The offset of the last byte of the resource range from `base`
+    pub max_offset: u64,
+}
+
+pub struct EnhancedAllocationEntryIterator<T: ConfigRegionAccess> {
+    address: PciAddress,
+    offset: u16,
+    remaining: u8,
+    access: T,
+}
+
+impl<T: ConfigRegionAccess> Iterator for EnhancedAllocationEntryIterator<T> {
+    type Item = EnhancedAllocationEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        let read = |offset: u16| unsafe { self.access.read(self.address, offset) };
+        let header = read(self.offset);
+        let base_low = read(self.offset + 0x04);
+        let max_offset_low = read(self.offset + 0x08);
+
+        /*
+         * Bit 1 of the Base and MaxOffset fields indicates that they are 64-bit, in which case their
+         * upper halves follow in that order. The low two bits of MaxOffset are implicitly `1`.
+         */
+        let mut next = self.offset + 0x0c;
+        let mut base = (base_low & !0b11) as u64;
+        if base_low.get_bit(1) {
+            base.set_bits(32..64, read(next) as u64);
+            next += 4;
+        }
+        let mut max_offset = (max_offset_low | 0b11) as u64;
+        if max_offset_low.get_bit(1) {
+            max_offset.set_bits(32..64, read(next) as u64);
+        }
+
+        /*
+         * The Entry Size field gives the number of dwords in the entry after the first, so is used to
+         * find the next entry, in case it contains fields we don't know about.
+         */
+        self.offset += 4 + header.get_bits(0..3) as u16 * 4;
+
+        Some(EnhancedAllocationEntry {
+            bar_equivalent_indicator: header.get_bits(4..8) as u8,
+            primary_properties: header.get_bits(8..16) as u8,
+            secondary_properties: header.get_bits(16..24) as u8,
+            writable: header.get_bit(30),
+            enabled: header.get_bit(31),
+            base,
+            max_offset,
+        })
+    }
+}
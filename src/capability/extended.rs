@@ -0,0 +1,136 @@
+use super::{
+    AcsCapability,
+    AerCapability,
+    AriCapability,
+    LtrCapability,
+    PasidCapability,
+    PciCapabilityAddress,
+    ResizableBarCapability,
+    TphCapability,
+    VendorSpecificExtendedCapability,
+    VirtualChannelCapability,
+};
+use crate::{ConfigRegionAccess, PciAddress};
+use bit_field::BitField;
+
+/// PCI Express extended capabilities, which live in extended configuration space, starting at offset
+/// `0x100`.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug)]
+pub enum PciExtendedCapability {
+    /// Advanced Error Reporting capability, Cap ID = `0x0001`
+    AdvancedErrorReporting(AerCapability),
+    /// Virtual Channel capability, Cap ID = `0x0002`, or `0x0009` if the device also has a Multi-Function
+    /// Virtual Channel capability
+    VirtualChannel(VirtualChannelCapability),
+    /// Vendor-Specific Extended capability, Cap ID = `0x000B`
+    VendorSpecificExtended(VendorSpecificExtendedCapability),
+    /// Access Control Services capability, Cap ID = `0x000D`
+    AccessControlServices(AcsCapability),
+    /// Alternative Routing-ID Interpretation capability, Cap ID = `0x000E`
+    AlternativeRoutingId(AriCapability),
+    /// Resizable BAR capability, Cap ID = `0x0015`
+    ResizableBar(ResizableBarCapability),
+    /// TLP Processing Hints Requester capability, Cap ID = `0x0017`
+    TphRequester(TphCapability),
+    /// Latency Tolerance Reporting capability, Cap ID = `0x0018`
+    LatencyToleranceReporting(LtrCapability),
+    /// Process Address Space ID capability, Cap ID = `0x001B`
+    ProcessAddressSpaceId(PasidCapability),
+    /// Unknown extended capability
+    Unknown { address: PciCapabilityAddress, id: u16, version: u8 },
+}
+
+impl PciExtendedCapability {
+    fn parse(
+        id: u16,
+        version: u8,
+        address: PciCapabilityAddress,
+        access: impl ConfigRegionAccess,
+    ) -> Option<PciExtendedCapability> {
+        match id {
+            0x0000 => None, // null capability
+            0x0001 => Some(PciExtendedCapability::AdvancedErrorReporting(AerCapability::new(address))),
+            0x0002 | 0x0009 => {
+                Some(PciExtendedCapability::VirtualChannel(VirtualChannelCapability::new(address, access)))
+            }
+            0x000B => Some(PciExtendedCapability::VendorSpecificExtended(VendorSpecificExtendedCapability::new(
+                address, access,
+            ))),
+            0x000D => Some(PciExtendedCapability::AccessControlServices(AcsCapability::new(address))),
+            0x000E => Some(PciExtendedCapability::AlternativeRoutingId(AriCapability::new(address))),
+            0x0015 => Some(PciExtendedCapability::ResizableBar(ResizableBarCapability::new(address, access))),
+            0x0017 => Some(PciExtendedCapability::TphRequester(TphCapability::new(address))),
+            0x0018 => Some(PciExtendedCapability::LatencyToleranceReporting(LtrCapability::new(address))),
+            0x001B => Some(PciExtendedCapability::ProcessAddressSpaceId(PasidCapability::new(address))),
+            _ => Some(PciExtendedCapability::Unknown { address, id, version }),
+        }
+    }
+
+    /// The location of the capability in extended configuration space. For every variant, `offset` is the
+    /// offset of the capability's header - exactly where it was found while walking the capability list.
+    pub fn address(&self) -> PciCapabilityAddress {
+        match *self {
+            PciExtendedCapability::AdvancedErrorReporting(aer_cap) => aer_cap.address,
+            PciExtendedCapability::VirtualChannel(vc_cap) => vc_cap.address,
+            PciExtendedCapability::VendorSpecificExtended(vsec_cap) => vsec_cap.address,
+            PciExtendedCapability::AccessControlServices(acs_cap) => acs_cap.address,
+            PciExtendedCapability::AlternativeRoutingId(ari_cap) => ari_cap.address,
+            PciExtendedCapability::ResizableBar(rebar_cap) => rebar_cap.address,
+            PciExtendedCapability::TphRequester(tph_cap) => tph_cap.address,
+            PciExtendedCapability::LatencyToleranceReporting(ltr_cap) => ltr_cap.address,
+            PciExtendedCapability::ProcessAddressSpaceId(pasid_cap) => pasid_cap.address,
+            PciExtendedCapability::Unknown { address, .. } => address,
+        }
+    }
+}
+
+pub struct ExtendedCapabilityIterator<T: ConfigRegionAccess> {
+    address: PciAddress,
+    offset: u16,
+    access: T,
+}
+
+impl<T: ConfigRegionAccess> ExtendedCapabilityIterator<T> {
+    pub(crate) fn new(address: PciAddress, access: T) -> ExtendedCapabilityIterator<T> {
+        ExtendedCapabilityIterator { address, offset: 0x100, access }
+    }
+}
+
+impl<T: ConfigRegionAccess> Iterator for ExtendedCapabilityIterator<T> {
+    type Item = PciExtendedCapability;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            /*
+             * The list ends with a next pointer of `0`. Next pointers must point into extended
+             * configuration space, so a smaller value is also treated as the end of the list.
+             */
+            if self.offset < 0x100 {
+                return None;
+            }
+            let data = unsafe { self.access.read(self.address, self.offset) };
+
+            /*
+             * If the function has no extended capabilities, the header at `0x100` is all zeros. If the
+             * function does not have extended configuration space at all, it reads as all ones.
+             */
+            if data == 0x0000_0000 || data == 0xffff_ffff {
+                return None;
+            }
+
+            let id = data.get_bits(0..16) as u16;
+            let version = data.get_bits(16..20) as u8;
+            let cap = PciExtendedCapability::parse(
+                id,
+                version,
+                PciCapabilityAddress { address: self.address, offset: self.offset },
+                &self.access,
+            );
+            self.offset = data.get_bits(22..32) as u16 * 4;
+            if let Some(cap) = cap {
+                return Some(cap);
+            }
+        }
+    }
+}
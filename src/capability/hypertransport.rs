@@ -0,0 +1,122 @@
+use super::PciCapabilityAddress;
+use crate::ConfigRegionAccess;
+use bit_field::BitField;
+
+/// The type of a HyperTransport capability, encoded in the upper bits of its Command register. The
+/// interface types use a 3-bit encoding, while all other types use a 5-bit encoding.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HyperTransportType {
+    SlaveOrPrimaryInterface,
+    HostOrSecondaryInterface,
+    InterruptDiscoveryAndConfiguration,
+    RevisionId,
+    UnitIdClumping,
+    ExtendedConfigurationSpaceAccess,
+    AddressMapping,
+    MsiMapping,
+    DirectRoute,
+    VirtualChannelSet,
+    RetryMode,
+    X86Encoding,
+    Gen3,
+    FunctionLevelExtension,
+    PowerManagement,
+    HighNodeCount,
+    Unknown(u8),
+}
+
+impl From<u8> for HyperTransportType {
+    fn from(value: u8) -> Self {
+        match value.get_bits(5..8) {
+            0b000 => return HyperTransportType::SlaveOrPrimaryInterface,
+            0b001 => return HyperTransportType::HostOrSecondaryInterface,
+            _ => (),
+        }
+        match value.get_bits(3..8) {
+            0b10000 => HyperTransportType::InterruptDiscoveryAndConfiguration,
+            0b10001 => HyperTransportType::RevisionId,
+            0b10010 => HyperTransportType::UnitIdClumping,
+            0b10011 => HyperTransportType::ExtendedConfigurationSpaceAccess,
+            0b10100 => HyperTransportType::AddressMapping,
+            0b10101 => HyperTransportType::MsiMapping,
+            0b10110 => HyperTransportType::DirectRoute,
+            0b10111 => HyperTransportType::VirtualChannelSet,
+            0b11000 => HyperTransportType::RetryMode,
+            0b11001 => HyperTransportType::X86Encoding,
+            0b11010 => HyperTransportType::Gen3,
+            0b11011 => HyperTransportType::FunctionLevelExtension,
+            0b11100 => HyperTransportType::PowerManagement,
+            0b11101 => HyperTransportType::HighNodeCount,
+            t => HyperTransportType::Unknown(t),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct HyperTransportCapability {
+    pub(super) address: PciCapabilityAddress,
+    capability_type: HyperTransportType,
+}
+
+impl HyperTransportCapability {
+    /// The address MSIs are mapped to when an MSI Mapping capability only supports a fixed mapping.
+    pub const FIXED_MSI_MAPPING_ADDRESS: u64 = 0xfee00000;
+
+    pub(crate) fn new(address: PciCapabilityAddress, command: u16) -> HyperTransportCapability {
+        HyperTransportCapability {
+            address,
+            capability_type: HyperTransportType::from(command.get_bits(8..16) as u8),
+        }
+    }
+
+    /// The type of this HyperTransport capability
+    #[inline]
+    pub fn capability_type(&self) -> HyperTransportType {
+        self.capability_type
+    }
+
+    /// Whether MSIs are mapped onto HyperTransport interrupt messages. Returns `None` if this is not an
+    /// MSI Mapping capability.
+    pub fn msi_mapping_enabled(&self, access: impl ConfigRegionAccess) -> Option<bool> {
+        if self.capability_type != HyperTransportType::MsiMapping {
+            return None;
+        }
+        let reg = unsafe { access.read(self.address.address, self.address.offset) };
+        Some(reg.get_bit(16))
+    }
+
+    /// Enable or disable the mapping of MSIs onto HyperTransport interrupt messages. Does nothing if this
+    /// is not an MSI Mapping capability.
+    pub fn set_msi_mapping_enabled(&self, enabled: bool, access: impl ConfigRegionAccess) {
+        if self.capability_type != HyperTransportType::MsiMapping {
+            return;
+        }
+        let mut reg = unsafe { access.read(self.address.address, self.address.offset) };
+        reg.set_bit(16, enabled);
+        unsafe { access.write(self.address.address, self.address.offset, reg) };
+    }
+
+    /// The base address of the window that MSI writes are mapped from. Returns `None` if this is not an
+    /// MSI Mapping capability.
+    ///
+    /// If the capability only supports a fixed mapping, this is always
+    /// [`HyperTransportCapability::FIXED_MSI_MAPPING_ADDRESS`].
+    pub fn msi_mapping_address(&self, access: impl ConfigRegionAccess) -> Option<u64> {
+        if self.capability_type != HyperTransportType::MsiMapping {
+            return None;
+        }
+        let reg = unsafe { access.read(self.address.address, self.address.offset) };
+        if reg.get_bit(17) {
+            return Some(Self::FIXED_MSI_MAPPING_ADDRESS);
+        }
+
+        let mut address = 0u64;
+        unsafe {
+            let low = access.read(self.address.address, self.address.offset + 0x04);
+            let high = access.read(self.address.address, self.address.offset + 0x08);
+            address.set_bits(20..32, low.get_bits(20..32) as u64);
+            address.set_bits(32..64, high as u64);
+        }
+        Some(address)
+    }
+}
@@ -0,0 +1,97 @@
+use super::PciCapabilityAddress;
+use crate::ConfigRegionAccess;
+use bit_field::BitField;
+use core::convert::TryFrom;
+
+/// The unit of a latency value reported through Latency Tolerance Reporting
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum LatencyScale {
+    Ns1 = 0b000,
+    Ns32 = 0b001,
+    Ns1024 = 0b010,
+    Ns32768 = 0b011,
+    Ns1048576 = 0b100,
+    Ns33554432 = 0b101,
+}
+
+impl TryFrom<u8> for LatencyScale {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0b000 => Ok(LatencyScale::Ns1),
+            0b001 => Ok(LatencyScale::Ns32),
+            0b010 => Ok(LatencyScale::Ns1024),
+            0b011 => Ok(LatencyScale::Ns32768),
+            0b100 => Ok(LatencyScale::Ns1048576),
+            0b101 => Ok(LatencyScale::Ns33554432),
+            _ => Err(()),
+        }
+    }
+}
+
+/// A latency tolerance, made up of a 10-bit value multiplied by a scale.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct LtrLatency {
+    /// The latency value. Only the low 10 bits are used.
+    pub value: u16,
+    pub scale: LatencyScale,
+}
+
+impl LtrLatency {
+    /// The latency in nanoseconds
+    pub fn nanoseconds(&self) -> u64 {
+        (self.value.get_bits(0..10) as u64) << (5 * self.scale as u64)
+    }
+
+    fn decode(reg: u16) -> Option<LtrLatency> {
+        let scale = LatencyScale::try_from(reg.get_bits(10..13) as u8).ok()?;
+        Some(LtrLatency { value: reg.get_bits(0..10), scale })
+    }
+
+    fn encode(&self) -> u16 {
+        let mut reg = 0;
+        reg.set_bits(0..10, self.value.get_bits(0..10));
+        reg.set_bits(10..13, self.scale as u16);
+        reg
+    }
+}
+
+/// The Latency Tolerance Reporting extended capability, which holds the maximum latencies the platform
+/// can report on behalf of the function's upstream port.
+#[derive(Clone, Copy, Debug)]
+pub struct LtrCapability {
+    pub(super) address: PciCapabilityAddress,
+}
+
+impl LtrCapability {
+    pub(crate) fn new(address: PciCapabilityAddress) -> LtrCapability {
+        LtrCapability { address }
+    }
+
+    /// The maximum snoop latency the function is permitted to report. Returns `None` if the register
+    /// contains a reserved scale.
+    pub fn max_snoop_latency(&self, access: impl ConfigRegionAccess) -> Option<LtrLatency> {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        LtrLatency::decode(reg.get_bits(0..16) as u16)
+    }
+
+    pub fn set_max_snoop_latency(&self, latency: LtrLatency, access: impl ConfigRegionAccess) {
+        let mut reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        reg.set_bits(0..16, latency.encode() as u32);
+        unsafe { access.write(self.address.address, self.address.offset + 0x04, reg) };
+    }
+
+    /// The maximum no-snoop latency the function is permitted to report. Returns `None` if the register
+    /// contains a reserved scale.
+    pub fn max_no_snoop_latency(&self, access: impl ConfigRegionAccess) -> Option<LtrLatency> {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        LtrLatency::decode(reg.get_bits(16..32) as u16)
+    }
+
+    pub fn set_max_no_snoop_latency(&self, latency: LtrLatency, access: impl ConfigRegionAccess) {
+        let mut reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        reg.set_bits(16..32, latency.encode() as u32);
+        unsafe { access.write(self.address.address, self.address.offset + 0x04, reg) };
+    }
+}
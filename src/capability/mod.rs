@@ -2,11 +2,65 @@ use crate::{ConfigRegionAccess, PciAddress};
 use bit_field::BitField;
 use core::fmt::Formatter;
 
+mod acs;
+mod aer;
+mod ari;
+mod debug_port;
+mod enhanced_allocation;
+mod extended;
+mod hypertransport;
+mod ltr;
 mod msi;
 mod msix;
+mod pasid;
+mod pcie;
+mod pcix;
+mod power_management;
+mod resizable_bar;
+mod tph;
+mod vendor_specific;
+mod virtual_channel;
 
-pub use msi::{MsiCapability, MultipleMessageSupport, TriggerMode};
-pub use msix::MsixCapability;
+pub use acs::{AcsCapability, AcsFlags};
+pub use aer::{AerCapability, ErrorSourceId, RootErrorStatus, TlpHeader, TlpKind, UncorrectableErrors};
+pub use ari::AriCapability;
+pub use debug_port::DebugPortCapability;
+pub use enhanced_allocation::{
+    EnhancedAllocationCapability,
+    EnhancedAllocationEntry,
+    EnhancedAllocationEntryIterator,
+};
+pub use extended::{ExtendedCapabilityIterator, PciExtendedCapability};
+pub use hypertransport::{HyperTransportCapability, HyperTransportType};
+pub use ltr::{LatencyScale, LtrCapability, LtrLatency};
+pub use msi::{MsiCapability, MsiError, MultipleMessageSupport, TriggerMode};
+pub use msix::{MsixCapability, MsixControl, MsixError, MsixTableEntry};
+pub use pasid::PasidCapability;
+pub use pcie::{
+    AspmControl,
+    DeviceCapabilities2,
+    DeviceControl2,
+    DevicePortType,
+    DeviceStatus,
+    IndicatorState,
+    LinkControl,
+    LinkSpeed,
+    PayloadSize,
+    PciExpressCapability,
+    PowerLimitScale,
+    RegisterNotPresent,
+    RootControl,
+    RootStatus,
+    SlotCapabilities,
+    SlotControl,
+    SlotStatus,
+};
+pub use pcix::{MaxMemoryReadByteCount, MaxOutstandingSplitTransactions, PciXCapability, PciXStatus};
+pub use power_management::{PmeSupport, PowerManagementCapability, PowerState, PowerStateSupport};
+pub use resizable_bar::{ResizableBarCapability, ResizableBarEntry, ResizableBarError};
+pub use tph::{StMode, StTableLocation, TphCapability, TphRequesterEnable};
+pub use vendor_specific::VendorSpecificExtendedCapability;
+pub use virtual_channel::{VcResourceControl, VirtualChannelCapability};
 
 #[derive(Clone, Copy)]
 pub struct PciCapabilityAddress {
@@ -14,17 +68,131 @@ pub struct PciCapabilityAddress {
     pub offset: u16,
 }
 
+impl PciCapabilityAddress {
+    /// Read the dword at `offset` bytes from the start of the capability. This is useful for building
+    /// wrappers around capabilities that this crate does not decode.
+    pub fn read_dword(&self, offset: u16, access: impl ConfigRegionAccess) -> u32 {
+        debug_assert_eq!(offset & 0b11, 0);
+        unsafe { access.read(self.address, self.offset + offset) }
+    }
+
+    /// Write the dword at `offset` bytes from the start of the capability.
+    pub fn write_dword(&self, offset: u16, value: u32, access: impl ConfigRegionAccess) {
+        debug_assert_eq!(offset & 0b11, 0);
+        unsafe { access.write(self.address, self.offset + offset, value) }
+    }
+
+    /// The raw first dword of the capability, containing its ID in bits `0..8`, the next pointer in bits
+    /// `8..16`, and a capability-specific field in bits `16..32`.
+    pub fn header(&self, access: impl ConfigRegionAccess) -> u32 {
+        self.read_dword(0x00, access)
+    }
+
+    /// The offset of the next capability in the list, or `0` if this is the last capability.
+    pub fn next_pointer(&self, access: impl ConfigRegionAccess) -> u16 {
+        self.header(access).get_bits(10..16) as u16 * 4
+    }
+}
+
 impl core::fmt::Debug for PciCapabilityAddress {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}, offset: {:02x}", self.address, self.offset)
     }
 }
 
+/// The IDs of PCI capabilities, including those that this crate does not decode.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CapabilityId {
+    Null,
+    PowerManagement,
+    AcceleratedGraphicsPort,
+    VitalProductData,
+    SlotIdentification,
+    Msi,
+    CompactPCIHotswap,
+    PciX,
+    HyperTransport,
+    Vendor,
+    DebugPort,
+    CompactPCICentralResourceControl,
+    PciHotPlugControl,
+    BridgeSubsystemVendorId,
+    AGP3,
+    SecureDevice,
+    PciExpress,
+    MsiX,
+    SataDataIndexConfiguration,
+    AdvancedFeatures,
+    EnhancedAllocation,
+    FlatteningPortalBridge,
+    Unknown(u8),
+}
+
+impl From<u8> for CapabilityId {
+    fn from(id: u8) -> Self {
+        match id {
+            0x00 => CapabilityId::Null,
+            0x01 => CapabilityId::PowerManagement,
+            0x02 => CapabilityId::AcceleratedGraphicsPort,
+            0x03 => CapabilityId::VitalProductData,
+            0x04 => CapabilityId::SlotIdentification,
+            0x05 => CapabilityId::Msi,
+            0x06 => CapabilityId::CompactPCIHotswap,
+            0x07 => CapabilityId::PciX,
+            0x08 => CapabilityId::HyperTransport,
+            0x09 => CapabilityId::Vendor,
+            0x0A => CapabilityId::DebugPort,
+            0x0B => CapabilityId::CompactPCICentralResourceControl,
+            0x0C => CapabilityId::PciHotPlugControl,
+            0x0D => CapabilityId::BridgeSubsystemVendorId,
+            0x0E => CapabilityId::AGP3,
+            0x0F => CapabilityId::SecureDevice,
+            0x10 => CapabilityId::PciExpress,
+            0x11 => CapabilityId::MsiX,
+            0x12 => CapabilityId::SataDataIndexConfiguration,
+            0x13 => CapabilityId::AdvancedFeatures,
+            0x14 => CapabilityId::EnhancedAllocation,
+            0x15 => CapabilityId::FlatteningPortalBridge,
+            id => CapabilityId::Unknown(id),
+        }
+    }
+}
+
+impl From<CapabilityId> for u8 {
+    fn from(id: CapabilityId) -> Self {
+        match id {
+            CapabilityId::Null => 0x00,
+            CapabilityId::PowerManagement => 0x01,
+            CapabilityId::AcceleratedGraphicsPort => 0x02,
+            CapabilityId::VitalProductData => 0x03,
+            CapabilityId::SlotIdentification => 0x04,
+            CapabilityId::Msi => 0x05,
+            CapabilityId::CompactPCIHotswap => 0x06,
+            CapabilityId::PciX => 0x07,
+            CapabilityId::HyperTransport => 0x08,
+            CapabilityId::Vendor => 0x09,
+            CapabilityId::DebugPort => 0x0A,
+            CapabilityId::CompactPCICentralResourceControl => 0x0B,
+            CapabilityId::PciHotPlugControl => 0x0C,
+            CapabilityId::BridgeSubsystemVendorId => 0x0D,
+            CapabilityId::AGP3 => 0x0E,
+            CapabilityId::SecureDevice => 0x0F,
+            CapabilityId::PciExpress => 0x10,
+            CapabilityId::MsiX => 0x11,
+            CapabilityId::SataDataIndexConfiguration => 0x12,
+            CapabilityId::AdvancedFeatures => 0x13,
+            CapabilityId::EnhancedAllocation => 0x14,
+            CapabilityId::FlatteningPortalBridge => 0x15,
+            CapabilityId::Unknown(id) => id,
+        }
+    }
+}
+
 /// PCI capabilities
 #[derive(Clone, Copy, Debug)]
 pub enum PciCapability {
     /// Power management capability, Cap ID = `0x01`
-    PowerManagement(PciCapabilityAddress),
+    PowerManagement(PowerManagementCapability),
     /// Accelerated graphics port capability, Cap ID = `0x02`
     AcceleratedGraphicsPort(PciCapabilityAddress),
     /// Vital product data capability, Cap ID = `0x3`
@@ -36,13 +204,13 @@ pub enum PciCapability {
     /// CompactPCI HotSwap capability, Cap ID = `0x06`
     CompactPCIHotswap(PciCapabilityAddress),
     /// PCI-X capability, Cap ID = `0x07`
-    PciX(PciCapabilityAddress),
+    PciX(PciXCapability),
     /// HyperTransport capability, Cap ID = `0x08`
-    HyperTransport(PciCapabilityAddress),
+    HyperTransport(HyperTransportCapability),
     /// Vendor-specific capability, Cap ID = `0x09`
     Vendor(PciCapabilityAddress),
     /// Debug port capability, Cap ID = `0x0A`
-    DebugPort(PciCapabilityAddress),
+    DebugPort(DebugPortCapability),
     /// CompactPCI Central Resource Control capability, Cap ID = `0x0B`
     CompactPCICentralResourceControl(PciCapabilityAddress),
     /// PCI Standard Hot-Plug Controller capability, Cap ID = `0x0C`
@@ -52,9 +220,11 @@ pub enum PciCapability {
     /// AGP Target PCI-PCI bridge capability, Cap ID = `0x0E`
     AGP3(PciCapabilityAddress),
     /// PCI Express capability, Cap ID = `0x10`
-    PciExpress(PciCapabilityAddress),
+    PciExpress(PciExpressCapability),
     /// MSI-X capability, Cap ID = `0x11`
     MsiX(MsixCapability),
+    /// Enhanced Allocation capability, Cap ID = `0x14`
+    EnhancedAllocation(EnhancedAllocationCapability),
     /// Unknown capability
     Unknown { address: PciCapabilityAddress, id: u8 },
 }
@@ -68,58 +238,152 @@ impl PciCapability {
     ) -> Option<PciCapability> {
         match id {
             0x00 => None, // null capability
-            0x01 => Some(PciCapability::PowerManagement(address)),
+            0x01 => Some(PciCapability::PowerManagement(PowerManagementCapability::new(address, extension))),
             0x02 => Some(PciCapability::AcceleratedGraphicsPort(address)),
             0x03 => Some(PciCapability::VitalProductData(address)),
             0x04 => Some(PciCapability::SlotIdentification(address)),
             0x05 => Some(PciCapability::Msi(MsiCapability::new(address, extension))),
             0x06 => Some(PciCapability::CompactPCIHotswap(address)),
-            0x07 => Some(PciCapability::PciX(address)),
-            0x08 => Some(PciCapability::HyperTransport(address)),
+            0x07 => Some(PciCapability::PciX(PciXCapability::new(address))),
+            0x08 => Some(PciCapability::HyperTransport(HyperTransportCapability::new(address, extension))),
             0x09 => Some(PciCapability::Vendor(address)),
-            0x0A => Some(PciCapability::DebugPort(address)),
+            0x0A => Some(PciCapability::DebugPort(DebugPortCapability::new(address, extension))),
             0x0B => Some(PciCapability::CompactPCICentralResourceControl(address)),
             0x0C => Some(PciCapability::PciHotPlugControl(address)),
             0x0D => Some(PciCapability::BridgeSubsystemVendorId(address)),
             0x0E => Some(PciCapability::AGP3(address)),
-            0x10 => Some(PciCapability::PciExpress(address)),
-            0x11 => Some(PciCapability::MsiX(MsixCapability::new(address, extension, access))),
+            0x10 => Some(PciCapability::PciExpress(PciExpressCapability::new(address, extension))),
+            0x11 => Some(PciCapability::MsiX(MsixCapability::new(address, extension, &access))),
+            0x14 => Some(PciCapability::EnhancedAllocation(EnhancedAllocationCapability::new(
+                address, extension, access,
+            ))),
             _ => Some(PciCapability::Unknown { address, id }),
         }
     }
 
+    /// The location of the capability in configuration space. For every variant, including those that
+    /// decode the capability into a typed structure, `offset` is the offset of the capability's header -
+    /// exactly where it was found while walking the capability list.
     pub fn address(&self) -> PciCapabilityAddress {
         match *self {
-            PciCapability::PowerManagement(address) => address,
+            PciCapability::PowerManagement(pm_cap) => pm_cap.address,
             PciCapability::AcceleratedGraphicsPort(address) => address,
             PciCapability::VitalProductData(address) => address,
             PciCapability::SlotIdentification(address) => address,
             PciCapability::Msi(msi_cap) => msi_cap.address,
             PciCapability::CompactPCIHotswap(address) => address,
-            PciCapability::PciX(address) => address,
-            PciCapability::HyperTransport(address) => address,
+            PciCapability::PciX(pcix_cap) => pcix_cap.address,
+            PciCapability::HyperTransport(ht_cap) => ht_cap.address,
             PciCapability::Vendor(address) => address,
-            PciCapability::DebugPort(address) => address,
+            PciCapability::DebugPort(debug_port_cap) => debug_port_cap.address,
             PciCapability::CompactPCICentralResourceControl(address) => address,
             PciCapability::PciHotPlugControl(address) => address,
             PciCapability::BridgeSubsystemVendorId(address) => address,
             PciCapability::AGP3(address) => address,
-            PciCapability::PciExpress(address) => address,
+            PciCapability::PciExpress(pcie_cap) => pcie_cap.address,
             PciCapability::MsiX(msix_cap) => msix_cap.address,
+            PciCapability::EnhancedAllocation(ea_cap) => ea_cap.address,
             PciCapability::Unknown { address, id: _ } => address,
         }
     }
+
+    /// The ID of the capability
+    pub fn id(&self) -> u8 {
+        match *self {
+            PciCapability::PowerManagement(_) => 0x01,
+            PciCapability::AcceleratedGraphicsPort(_) => 0x02,
+            PciCapability::VitalProductData(_) => 0x03,
+            PciCapability::SlotIdentification(_) => 0x04,
+            PciCapability::Msi(_) => 0x05,
+            PciCapability::CompactPCIHotswap(_) => 0x06,
+            PciCapability::PciX(_) => 0x07,
+            PciCapability::HyperTransport(_) => 0x08,
+            PciCapability::Vendor(_) => 0x09,
+            PciCapability::DebugPort(_) => 0x0A,
+            PciCapability::CompactPCICentralResourceControl(_) => 0x0B,
+            PciCapability::PciHotPlugControl(_) => 0x0C,
+            PciCapability::BridgeSubsystemVendorId(_) => 0x0D,
+            PciCapability::AGP3(_) => 0x0E,
+            PciCapability::PciExpress(_) => 0x10,
+            PciCapability::MsiX(_) => 0x11,
+            PciCapability::EnhancedAllocation(_) => 0x14,
+            PciCapability::Unknown { address: _, id } => id,
+        }
+    }
+
+    /// Read the capability-specific field in the upper half of the capability's first dword. For many
+    /// capabilities this is a control register.
+    pub fn control_word(&self, access: impl ConfigRegionAccess) -> u16 {
+        self.address().read_dword(0x00, access).get_bits(16..32) as u16
+    }
+}
+
+/// The most capabilities that can fit in the capability area of configuration space (`0x40..0x100`). Walks
+/// of the capability list stop after this many, so a list that loops does not hang them.
+const MAX_CAPABILITIES: u8 = ((0x100 - 0x40) / 4) as u8;
+
+/// Walk the capability list of the function at `address`, starting from the capability at `offset`, and
+/// return the offset of the first capability with the given ID. Unlike [`CapabilityIterator`], this does not
+/// parse the capabilities it passes over.
+pub(crate) fn find_capability(
+    address: PciAddress,
+    offset: u16,
+    id: u8,
+    access: impl ConfigRegionAccess,
+) -> Option<u16> {
+    RawCapabilityIterator::new(address, offset, access)
+        .find(|(cap_id, _)| u8::from(*cap_id) == id)
+        .map(|(_, cap_address)| cap_address.offset)
+}
+
+/// Iterates over the capability list of a function, yielding the ID and address of each capability
+/// without decoding it. This avoids the configuration space reads that [`CapabilityIterator`] makes to
+/// construct some capabilities, which makes it cheaper for listing capabilities. Unlike
+/// [`CapabilityIterator`], null capabilities are included.
+pub struct RawCapabilityIterator<T: ConfigRegionAccess> {
+    address: PciAddress,
+    offset: u16,
+    access: T,
+    remaining: u8,
+}
+
+impl<T: ConfigRegionAccess> RawCapabilityIterator<T> {
+    pub(crate) fn new(address: PciAddress, offset: u16, access: T) -> RawCapabilityIterator<T> {
+        RawCapabilityIterator { address, offset, access, remaining: MAX_CAPABILITIES }
+    }
+}
+
+impl<T: ConfigRegionAccess> Iterator for RawCapabilityIterator<T> {
+    type Item = (CapabilityId, PciCapabilityAddress);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset == 0 || self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let address = PciCapabilityAddress { address: self.address, offset: self.offset };
+        let data = address.header(&self.access);
+        // An all-ones header means the function has stopped responding
+        if data == 0xffff_ffff {
+            self.offset = 0;
+            return None;
+        }
+        // The bottom two bits of the next pointer are reserved
+        self.offset = data.get_bits(10..16) as u16 * 4;
+        Some((CapabilityId::from(data.get_bits(0..8) as u8), address))
+    }
 }
 
 pub struct CapabilityIterator<T: ConfigRegionAccess> {
     address: PciAddress,
     offset: u16,
     access: T,
+    remaining: u8,
 }
 
 impl<T: ConfigRegionAccess> CapabilityIterator<T> {
     pub(crate) fn new(address: PciAddress, offset: u16, access: T) -> CapabilityIterator<T> {
-        CapabilityIterator { address, offset, access }
+        CapabilityIterator { address, offset, access, remaining: MAX_CAPABILITIES }
     }
 }
 
@@ -128,19 +392,27 @@ impl<T: ConfigRegionAccess> Iterator for CapabilityIterator<T> {
 
     fn next(&mut self) -> Option<Self::Item> {
         loop {
-            if self.offset == 0 {
+            if self.offset == 0 || self.remaining == 0 {
+                return None;
+            }
+            self.remaining -= 1;
+            let address = PciCapabilityAddress { address: self.address, offset: self.offset };
+            let data = address.header(&self.access);
+            // An all-ones header means the function has stopped responding
+            if data == 0xffff_ffff {
+                self.offset = 0;
                 return None;
             }
-            let data = unsafe { self.access.read(self.address, self.offset) };
-            let next_ptr = data.get_bits(8..16);
+            // The bottom two bits of the next pointer are reserved
+            let next_ptr = data.get_bits(10..16) * 4;
             let id = data.get_bits(0..8);
             let extension = data.get_bits(16..32) as u16;
-            let cap = PciCapability::parse(
-                id as u8,
-                PciCapabilityAddress { address: self.address, offset: self.offset },
-                extension,
-                &self.access,
-            );
+            let cap = PciCapability::parse(id as u8, address, extension, &self.access);
+            /*
+             * Null capabilities (ID `0x00`) are not returned, but still have a valid next pointer, so the
+             * walk must continue past them rather than ending the list. The list only ends at a next
+             * pointer of `0`.
+             */
             self.offset = next_ptr as u16;
             if let Some(cap) = cap {
                 return Some(cap);
@@ -21,6 +21,13 @@ pub enum MultipleMessageSupport {
     Int32 = 0b101,
 }
 
+impl MultipleMessageSupport {
+    /// The number of interrupts
+    pub fn count(&self) -> u8 {
+        1 << (*self as u8)
+    }
+}
+
 impl TryFrom<u8> for MultipleMessageSupport {
     type Error = ();
 
@@ -45,6 +52,13 @@ pub enum TriggerMode {
     LevelDeassert = 0b10,
 }
 
+/// Errors found when configuring an MSI capability.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MsiError {
+    /// The message address does not fit in 32 bits, but the device only supports 32-bit addressing
+    AddressTooWide,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct MsiCapability {
     pub(super) address: PciCapabilityAddress,
@@ -86,6 +100,17 @@ impl MsiCapability {
         unsafe { access.read(self.address.address, self.address.offset) }
     }
 
+    /// Read the Message Control register
+    pub fn message_control(&self, access: impl ConfigRegionAccess) -> u16 {
+        self.ctrl(access).get_bits(16..32) as u16
+    }
+
+    /// Re-read the Message Control register and update the cached properties of the capability (whether
+    /// it is 64-bit, supports per-vector masking, and how many interrupts it can send) from it.
+    pub fn refresh(&mut self, access: impl ConfigRegionAccess) {
+        *self = MsiCapability::new(self.address, self.message_control(access));
+    }
+
     /// Is MSI capability enabled?
     pub fn is_enabled(&self, access: impl ConfigRegionAccess) -> bool {
         let reg = unsafe { access.read(self.address.address, self.address.offset) };
@@ -107,25 +132,91 @@ impl MsiCapability {
         unsafe { access.write(self.address.address, self.address.offset, reg) };
     }
 
+    /// Request that the device uses `desired` interrupts, returning how many it has actually been
+    /// configured to use.
+    ///
+    /// The requested count is rounded up to the next power of two (to a maximum of 32), and then, like
+    /// [`MsiCapability::set_multiple_message_enable`], clamped to the number of interrupts the device
+    /// supports. The caller should check the returned count to see whether it got fewer interrupts than it
+    /// asked for.
+    pub fn request_vectors(&self, desired: u8, access: impl ConfigRegionAccess) -> MultipleMessageSupport {
+        let requested = match desired {
+            0..=1 => MultipleMessageSupport::Int1,
+            2 => MultipleMessageSupport::Int2,
+            3..=4 => MultipleMessageSupport::Int4,
+            5..=8 => MultipleMessageSupport::Int8,
+            9..=16 => MultipleMessageSupport::Int16,
+            _ => MultipleMessageSupport::Int32,
+        };
+        self.set_multiple_message_enable(requested, access);
+        requested.min(self.multiple_message_capable)
+    }
+
     /// Return how many interrupts the device is using
     pub fn multiple_message_enable(&self, access: impl ConfigRegionAccess) -> MultipleMessageSupport {
         let reg = unsafe { access.read(self.address.address, self.address.offset) };
         MultipleMessageSupport::try_from(reg.get_bits(4..7) as u8).unwrap_or(MultipleMessageSupport::Int1)
     }
 
+    /// The offset, within configuration space, of the Message Address register. On devices that use
+    /// 64-bit addressing, this is the lower half of the address, and the upper half follows it.
+    pub fn message_address_offset(&self) -> u16 {
+        self.address.offset + 0x04
+    }
+
+    /// The offset, within configuration space, of the Message Data register
+    pub fn message_data_offset(&self) -> u16 {
+        if self.is_64bit {
+            self.address.offset + 0x0c
+        } else {
+            self.address.offset + 0x08
+        }
+    }
+
+    /// The offset, within configuration space, of the Mask Bits register, or `None` if the device does not
+    /// support per-vector masking
+    pub fn mask_offset(&self) -> Option<u16> {
+        if !self.per_vector_masking {
+            return None;
+        }
+        if self.is_64bit {
+            Some(self.address.offset + 0x10)
+        } else {
+            Some(self.address.offset + 0x0c)
+        }
+    }
+
+    /// The offset, within configuration space, of the Pending Bits register, or `None` if the device does
+    /// not support per-vector masking
+    pub fn pending_offset(&self) -> Option<u16> {
+        self.mask_offset().map(|offset| offset + 0x04)
+    }
+
     /// Set the memory address that will be written to when the interrupt fires, and the data that
     /// will be written to it.
-    pub fn set_message_info(&self, address: u64, data: u32, access: impl ConfigRegionAccess) {
+    ///
+    /// Returns [`MsiError::AddressTooWide`], without changing the capability, if `address` does not fit in
+    /// 32 bits and the device does not support 64-bit addressing.
+    pub fn set_message_info(
+        &self,
+        address: u64,
+        data: u32,
+        access: impl ConfigRegionAccess,
+    ) -> Result<(), MsiError> {
+        if !self.is_64bit && address > u32::MAX as u64 {
+            return Err(MsiError::AddressTooWide);
+        }
+        let address_offset = self.message_address_offset();
         unsafe {
-            access.write(self.address.address, self.address.offset + 0x04, address.get_bits(0..32) as u32);
+            access.write(self.address.address, address_offset, address.get_bits(0..32) as u32);
             if self.is_64bit {
-                access.write(self.address.address, self.address.offset + 0x08, address.get_bits(32..64) as u32);
+                access.write(self.address.address, address_offset + 0x04, address.get_bits(32..64) as u32);
             }
         }
-        let data_offset = if self.is_64bit { 0x0c } else { 0x08 };
         unsafe {
-            access.write(self.address.address, self.address.offset + data_offset, data);
+            access.write(self.address.address, self.message_data_offset(), data);
         }
+        Ok(())
     }
 
     /// Set the memory address that will be written to when the interrupt fires, and the data that
@@ -136,52 +227,50 @@ impl MsiCapability {
     /// * `vector` - Which interrupt vector should be triggered on LAPIC
     /// * `trigger_mode` - When interrupt should be triggered
     /// * `access` - PCI Configuration Space accessor
+    ///
+    /// Like [`MsiCapability::set_message_info`], this fails if `address` is too wide for the device.
     pub fn set_message_info_lapic(
         &self,
         address: u64,
         vector: u8,
         trigger_mode: TriggerMode,
         access: impl ConfigRegionAccess,
-    ) {
+    ) -> Result<(), MsiError> {
         let mut data = 0;
         data.set_bits(0..8, vector as u32);
         data.set_bits(14..16, trigger_mode as u32);
-        self.set_message_info(address, data, access);
+        self.set_message_info(address, data, access)
     }
 
     /// Get interrupt mask
     ///
     /// # Note
-    /// Only supported on when device supports 64-bit addressing and per-vector masking. Otherwise
-    /// returns `0`
+    /// Only supported on when device supports per-vector masking. Otherwise returns `0`
     pub fn message_mask(&self, access: impl ConfigRegionAccess) -> u32 {
-        if self.is_64bit && self.per_vector_masking {
-            unsafe { access.read(self.address.address, self.address.offset + 0x10) }
-        } else {
-            0
+        match self.mask_offset() {
+            Some(offset) => unsafe { access.read(self.address.address, offset) },
+            None => 0,
         }
     }
 
     /// Set interrupt mask
     ///
     /// # Note
-    /// Only supported on when device supports 64-bit addressing and per-vector masking. Otherwise
-    /// will do nothing
+    /// Only supported on when device supports per-vector masking. Otherwise will do nothing
     pub fn set_message_mask(&self, mask: u32, access: impl ConfigRegionAccess) {
-        if self.is_64bit && self.per_vector_masking {
-            unsafe { access.write(self.address.address, self.address.offset + 0x10, mask) }
+        if let Some(offset) = self.mask_offset() {
+            unsafe { access.write(self.address.address, offset, mask) }
         }
     }
 
     /// Get pending interrupts
     ///
     /// # Note
-    /// Only supported on when device supports 64-bit addressing. Otherwise will return `0`
+    /// Only supported on when device supports per-vector masking. Otherwise will return `0`
     pub fn is_pending(&self, access: impl ConfigRegionAccess) -> u32 {
-        if self.is_64bit {
-            unsafe { access.read(self.address.address, self.address.offset + 0x14) }
-        } else {
-            0
+        match self.pending_offset() {
+            Some(offset) => unsafe { access.read(self.address.address, offset) },
+            None => 0,
         }
     }
 }
@@ -1,7 +1,31 @@
 use super::PciCapabilityAddress;
-use crate::ConfigRegionAccess;
+use crate::{Bar, ConfigRegionAccess, EndpointHeader};
 use bit_field::BitField;
 
+/// Errors found when validating the layout of an MSI-X capability against the BARs of its function.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MsixError {
+    /// The BAR containing the MSI-X table is not implemented, or is not a memory BAR
+    InvalidTableBar,
+    /// The BAR containing the Pending Bit Array is not implemented, or is not a memory BAR
+    InvalidPbaBar,
+    /// The MSI-X table extends past the end of its BAR
+    TableOutOfBounds,
+    /// The Pending Bit Array extends past the end of its BAR
+    PbaOutOfBounds,
+    /// The MSI-X table and Pending Bit Array are in the same BAR, and overlap
+    TablePbaOverlap,
+}
+
+/// The state of the MSI-X Message Control register, as returned by [`MsixCapability::control`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MsixControl {
+    pub enabled: bool,
+    pub function_mask: bool,
+    /// The number of entries in the MSI-X table
+    pub table_size: u16,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct MsixCapability {
     pub(super) address: PciCapabilityAddress,
@@ -61,9 +85,37 @@ impl MsixCapability {
         control.get_bit(30)
     }
 
-    /// The index of the BAR that contains the MSI-X table.
-    pub fn table_bar(&self) -> u8 {
-        self.table.get_bits(0..3) as u8
+    /// Read the Enable and Function Mask bits, and the table size, from the Message Control register with
+    /// a single read.
+    pub fn control(&self, access: impl ConfigRegionAccess) -> MsixControl {
+        let control = unsafe { access.read(self.address.address, self.address.offset) };
+        MsixControl {
+            enabled: control.get_bit(31),
+            function_mask: control.get_bit(30),
+            table_size: control.get_bits(16..27) as u16 + 1,
+        }
+    }
+
+    /// Restore the Enable and Function Mask bits saved with [`MsixCapability::control`]. The table size is
+    /// read-only, so is ignored.
+    ///
+    /// The function is masked while MSI-X is enabled or disabled, and only then is the saved mask
+    /// applied, so the function can't send an interrupt while its enable state is changing.
+    pub fn restore_control(&self, control: MsixControl, access: impl ConfigRegionAccess) {
+        let mut reg = unsafe { access.read(self.address.address, self.address.offset) };
+        reg.set_bit(30, true);
+        unsafe { access.write(self.address.address, self.address.offset, reg) };
+        reg.set_bit(31, control.enabled);
+        unsafe { access.write(self.address.address, self.address.offset, reg) };
+        reg.set_bit(30, control.function_mask);
+        unsafe { access.write(self.address.address, self.address.offset, reg) };
+    }
+
+    /// The index of the BAR that contains the MSI-X table. Returns `None` if the device reports an index
+    /// that does not correspond to a BAR slot.
+    pub fn table_bar(&self) -> Option<u8> {
+        let bir = self.table.get_bits(0..3) as u8;
+        (bir < 6).then_some(bir)
     }
 
     /// The offset, in bytes, of the MSI-X table within its BAR.
@@ -79,8 +131,11 @@ impl MsixCapability {
         self.table_size
     }
 
-    pub fn pba_bar(&self) -> u8 {
-        self.pba.get_bits(0..3) as u8
+    /// The index of the BAR that contains the Pending Bit Array. Returns `None` if the device reports an
+    /// index that does not correspond to a BAR slot.
+    pub fn pba_bar(&self) -> Option<u8> {
+        let bir = self.pba.get_bits(0..3) as u8;
+        (bir < 6).then_some(bir)
     }
 
     pub fn pba_offset(&self) -> u32 {
@@ -90,4 +145,130 @@ impl MsixCapability {
          */
         self.pba & !0b111
     }
+
+    /// Returns `true` if the MSI-X table and Pending Bit Array live in the same BAR. This is permitted, as
+    /// long as they do not overlap, which is checked by [`MsixCapability::validate`].
+    pub fn table_pba_same_bar(&self) -> bool {
+        self.table.get_bits(0..3) == self.pba.get_bits(0..3)
+    }
+
+    /// Check that the MSI-X table and Pending Bit Array fit within the BARs that they are reported to
+    /// live in, and that they do not overlap if they share a BAR. This catches devices that advertise a
+    /// bogus table size or offset before their structures are mapped.
+    ///
+    /// This decodes the BARs with [`EndpointHeader::bar`], and so probes their sizes.
+    pub fn validate(&self, endpoint: &EndpointHeader, access: impl ConfigRegionAccess) -> Result<(), MsixError> {
+        let table_end = self.table_offset() as u64 + self.table_size as u64 * 16;
+        match memory_bar_size(self.table_bar().and_then(|bar| endpoint.bar(bar, &access))) {
+            Some(size) if table_end <= size => (),
+            Some(_) => return Err(MsixError::TableOutOfBounds),
+            None => return Err(MsixError::InvalidTableBar),
+        }
+
+        /*
+         * The PBA contains one bit per table entry, and is made up of whole qwords.
+         */
+        let pba_end = self.pba_offset() as u64 + (self.table_size as u64).div_ceil(64) * 8;
+        match memory_bar_size(self.pba_bar().and_then(|bar| endpoint.bar(bar, &access))) {
+            Some(size) if pba_end <= size => (),
+            Some(_) => return Err(MsixError::PbaOutOfBounds),
+            None => return Err(MsixError::InvalidPbaBar),
+        }
+
+        let overlaps = (self.table_offset() as u64) < pba_end && (self.pba_offset() as u64) < table_end;
+        if self.table_pba_same_bar() && overlaps {
+            return Err(MsixError::TablePbaOverlap);
+        }
+
+        Ok(())
+    }
+
+    /// Call `f` with each entry of the MSI-X table, along with its index. The physical address of the
+    /// table is resolved through the function's BARs, and passed to `map`, which should map it and return
+    /// a pointer through which it can be accessed.
+    ///
+    /// This decodes the table's BAR with [`EndpointHeader::bar`], and so probes its size.
+    ///
+    /// # Safety
+    ///
+    /// The pointer returned by `map` must be valid for volatile reads and writes of the whole table
+    /// (`table_size * 16` bytes), and the memory must be mapped such that accesses reach the device (e.g.
+    /// uncached).
+    pub unsafe fn for_each_entry<M, F>(
+        &self,
+        endpoint: &EndpointHeader,
+        access: impl ConfigRegionAccess,
+        map: M,
+        mut f: F,
+    ) -> Result<(), MsixError>
+    where
+        M: FnOnce(u64) -> *mut u32,
+        F: FnMut(u16, &mut MsixTableEntry),
+    {
+        let bar_address = match self.table_bar().and_then(|bar| endpoint.bar(bar, &access)) {
+            Some(Bar::Memory32 { address, .. }) => address as u64,
+            Some(Bar::Memory64 { address, .. }) => address,
+            _ => return Err(MsixError::InvalidTableBar),
+        };
+
+        let table = map(bar_address + self.table_offset() as u64);
+        for index in 0..self.table_size {
+            let mut entry = MsixTableEntry { ptr: unsafe { table.add(index as usize * 4) } };
+            f(index, &mut entry);
+        }
+        Ok(())
+    }
+}
+
+/// An entry of an MSI-X table, accessed through memory that has been mapped by the caller of
+/// [`MsixCapability::for_each_entry`].
+pub struct MsixTableEntry {
+    ptr: *mut u32,
+}
+
+impl MsixTableEntry {
+    pub fn message_address(&self) -> u64 {
+        let mut address = 0u64;
+        unsafe {
+            address.set_bits(0..32, self.ptr.read_volatile() as u64);
+            address.set_bits(32..64, self.ptr.add(1).read_volatile() as u64);
+        }
+        address
+    }
+
+    pub fn set_message_address(&mut self, address: u64) {
+        unsafe {
+            self.ptr.write_volatile(address.get_bits(0..32) as u32);
+            self.ptr.add(1).write_volatile(address.get_bits(32..64) as u32);
+        }
+    }
+
+    pub fn message_data(&self) -> u32 {
+        unsafe { self.ptr.add(2).read_volatile() }
+    }
+
+    pub fn set_message_data(&mut self, data: u32) {
+        unsafe { self.ptr.add(2).write_volatile(data) }
+    }
+
+    /// Whether this entry is masked, preventing the function from sending its message.
+    pub fn masked(&self) -> bool {
+        unsafe { self.ptr.add(3).read_volatile() }.get_bit(0)
+    }
+
+    pub fn set_masked(&mut self, masked: bool) {
+        unsafe {
+            let mut control = self.ptr.add(3).read_volatile();
+            control.set_bit(0, masked);
+            self.ptr.add(3).write_volatile(control);
+        }
+    }
+}
+
+fn memory_bar_size(bar: Option<Bar>) -> Option<u64> {
+    match bar? {
+        Bar::Memory32 { size, .. } => Some(size as u64),
+        Bar::Memory64 { size, .. } => Some(size),
+        Bar::Io { .. } => None,
+    }
 }
@@ -0,0 +1,80 @@
+use super::PciCapabilityAddress;
+use crate::ConfigRegionAccess;
+use bit_field::BitField;
+
+/// The Process Address Space ID (PASID) extended capability. When enabled, the function can tag its
+/// requests with a PASID, allowing it to access memory in the address space of a particular process
+/// through an IOMMU (e.g. for shared virtual memory).
+#[derive(Clone, Copy, Debug)]
+pub struct PasidCapability {
+    pub(super) address: PciCapabilityAddress,
+}
+
+impl PasidCapability {
+    pub(crate) fn new(address: PciCapabilityAddress) -> PasidCapability {
+        PasidCapability { address }
+    }
+
+    /// Whether the function supports requesting Execute permission with a PASID
+    pub fn exec_permission_supported(&self, access: impl ConfigRegionAccess) -> bool {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        reg.get_bit(1)
+    }
+
+    /// Whether the function supports requesting Privileged Mode with a PASID
+    pub fn privileged_mode_supported(&self, access: impl ConfigRegionAccess) -> bool {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        reg.get_bit(2)
+    }
+
+    /// The number of bits of PASID the function supports. The function can use PASIDs in the range
+    /// `0..(1 << width)`.
+    pub fn max_pasid_width(&self, access: impl ConfigRegionAccess) -> u8 {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        reg.get_bits(8..13) as u8
+    }
+
+    /// Whether the function is permitted to send requests with a PASID
+    pub fn is_enabled(&self, access: impl ConfigRegionAccess) -> bool {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        reg.get_bit(16)
+    }
+
+    /// Enable or disable the use of PASIDs by the function. This should only be changed while the function
+    /// has no outstanding requests.
+    pub fn set_enabled(&self, enabled: bool, access: impl ConfigRegionAccess) {
+        self.update_control(16, enabled, access);
+    }
+
+    /// Whether the function is permitted to request Execute permission
+    pub fn exec_permission_enabled(&self, access: impl ConfigRegionAccess) -> bool {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        reg.get_bit(17)
+    }
+
+    /// Permit or forbid the function requesting Execute permission. Has no effect if the function does not
+    /// support it.
+    pub fn set_exec_permission(&self, enabled: bool, access: impl ConfigRegionAccess) {
+        self.update_control(17, enabled, access);
+    }
+
+    /// Whether the function is permitted to request Privileged Mode
+    pub fn privileged_mode_enabled(&self, access: impl ConfigRegionAccess) -> bool {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        reg.get_bit(18)
+    }
+
+    /// Permit or forbid the function requesting Privileged Mode. Has no effect if the function does not
+    /// support it.
+    pub fn set_privileged(&self, enabled: bool, access: impl ConfigRegionAccess) {
+        self.update_control(18, enabled, access);
+    }
+
+    fn update_control(&self, bit: usize, value: bool, access: impl ConfigRegionAccess) {
+        let mut reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        reg.set_bit(bit, value);
+        unsafe {
+            access.write(self.address.address, self.address.offset + 0x04, reg);
+        }
+    }
+}
@@ -0,0 +1,1059 @@
+use super::PciCapabilityAddress;
+use crate::ConfigRegionAccess;
+use bit_field::BitField;
+use core::{
+    convert::TryFrom,
+    fmt::{self, Debug, Formatter},
+};
+
+/// The type of PCI Express device or port, as reported in the PCI Express Capabilities register.
+/// This determines which of the capability's registers are implemented.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DevicePortType {
+    /// PCI Express Endpoint
+    Endpoint,
+    /// Legacy PCI Express Endpoint
+    LegacyEndpoint,
+    /// Root Port of a PCI Express Root Complex
+    RootPort,
+    /// Upstream Port of a PCI Express Switch
+    UpstreamSwitchPort,
+    /// Downstream Port of a PCI Express Switch
+    DownstreamSwitchPort,
+    /// PCI Express to PCI/PCI-X Bridge
+    PcieToPciBridge,
+    /// PCI/PCI-X to PCI Express Bridge
+    PciToPcieBridge,
+    /// Root Complex Integrated Endpoint
+    RootComplexIntegratedEndpoint,
+    /// Root Complex Event Collector
+    RootComplexEventCollector,
+    /// A reserved encoding
+    Unknown(u8),
+}
+
+impl From<u8> for DevicePortType {
+    fn from(value: u8) -> Self {
+        match value {
+            0b0000 => DevicePortType::Endpoint,
+            0b0001 => DevicePortType::LegacyEndpoint,
+            0b0100 => DevicePortType::RootPort,
+            0b0101 => DevicePortType::UpstreamSwitchPort,
+            0b0110 => DevicePortType::DownstreamSwitchPort,
+            0b0111 => DevicePortType::PcieToPciBridge,
+            0b1000 => DevicePortType::PciToPcieBridge,
+            0b1001 => DevicePortType::RootComplexIntegratedEndpoint,
+            0b1010 => DevicePortType::RootComplexEventCollector,
+            t => DevicePortType::Unknown(t),
+        }
+    }
+}
+
+/// Payload and read request sizes used by the Device Capabilities and Device Control registers.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum PayloadSize {
+    Bytes128 = 0b000,
+    Bytes256 = 0b001,
+    Bytes512 = 0b010,
+    Bytes1024 = 0b011,
+    Bytes2048 = 0b100,
+    Bytes4096 = 0b101,
+}
+
+impl PayloadSize {
+    /// The size in bytes
+    pub fn bytes(&self) -> usize {
+        128 << (*self as usize)
+    }
+}
+
+impl TryFrom<u8> for PayloadSize {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0b000 => Ok(PayloadSize::Bytes128),
+            0b001 => Ok(PayloadSize::Bytes256),
+            0b010 => Ok(PayloadSize::Bytes512),
+            0b011 => Ok(PayloadSize::Bytes1024),
+            0b100 => Ok(PayloadSize::Bytes2048),
+            0b101 => Ok(PayloadSize::Bytes4096),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Returned when accessing a register of the PCI Express capability that the device does not
+/// implement.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RegisterNotPresent;
+
+impl fmt::Display for RegisterNotPresent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "The register is not implemented by this PCI Express device")
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct PciExpressCapability {
+    pub(super) address: PciCapabilityAddress,
+    device_port_type: DevicePortType,
+    slot_implemented: bool,
+}
+
+impl PciExpressCapability {
+    pub(crate) fn new(address: PciCapabilityAddress, capabilities: u16) -> PciExpressCapability {
+        PciExpressCapability {
+            address,
+            device_port_type: DevicePortType::from(capabilities.get_bits(4..8) as u8),
+            slot_implemented: capabilities.get_bit(8),
+        }
+    }
+
+    /// The version of the PCI Express capability structure implemented by the device. Devices
+    /// compliant with PCI Express 2.0 and later report version `2`.
+    pub fn version(&self, access: impl ConfigRegionAccess) -> u8 {
+        let reg = unsafe { access.read(self.address.address, self.address.offset) };
+        reg.get_bits(16..20) as u8
+    }
+
+    /// The type of PCI Express device or port this function is.
+    #[inline]
+    pub fn device_port_type(&self) -> DevicePortType {
+        self.device_port_type
+    }
+
+    /// The largest payload size the function supports for TLPs.
+    pub fn max_payload_supported(&self, access: impl ConfigRegionAccess) -> PayloadSize {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        PayloadSize::try_from(reg.get_bits(0..3) as u8).unwrap_or(PayloadSize::Bytes128)
+    }
+
+    /// The maximum payload size the function is currently permitted to use for TLPs.
+    pub fn max_payload_size(&self, access: impl ConfigRegionAccess) -> PayloadSize {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x08) };
+        PayloadSize::try_from(reg.get_bits(5..8) as u8).unwrap_or(PayloadSize::Bytes128)
+    }
+
+    /// Set the maximum payload size the function can use for TLPs. This must not be larger than the size
+    /// reported by [`PciExpressCapability::max_payload_supported`], and should generally be programmed to
+    /// the same value across the whole hierarchy.
+    pub fn set_max_payload_size(&self, size: PayloadSize, access: impl ConfigRegionAccess) {
+        self.update_device_control(access, |control| {
+            control.set_bits(5..8, size as u32);
+        });
+    }
+
+    /// The maximum size of a read request the function can make.
+    pub fn max_read_request_size(&self, access: impl ConfigRegionAccess) -> PayloadSize {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x08) };
+        PayloadSize::try_from(reg.get_bits(12..15) as u8).unwrap_or(PayloadSize::Bytes128)
+    }
+
+    /// Set the maximum size of a read request the function can make.
+    pub fn set_max_read_request_size(&self, size: PayloadSize, access: impl ConfigRegionAccess) {
+        self.update_device_control(access, |control| {
+            control.set_bits(12..15, size as u32);
+        });
+    }
+
+    /// Read the Device Status register.
+    pub fn device_status(&self, access: impl ConfigRegionAccess) -> DeviceStatus {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x08) };
+        DeviceStatus::from_bits_retain(reg.get_bits(16..32) as u16)
+    }
+
+    /// Whether the function has issued non-posted requests that have not been completed. This must be
+    /// clear before a Function Level Reset can be performed safely.
+    pub fn transactions_pending(&self, access: impl ConfigRegionAccess) -> bool {
+        self.device_status(access).contains(DeviceStatus::TRANSACTIONS_PENDING)
+    }
+
+    /// Clear the error bits of the Device Status register that are set in `mask`, by writing `1` to
+    /// them, while preserving the Device Control register.
+    pub fn clear_device_status(&self, mask: DeviceStatus, access: impl ConfigRegionAccess) {
+        let mut reg = unsafe { access.read(self.address.address, self.address.offset + 0x08) };
+        reg.set_bits(16..32, mask.bits() as u32);
+        unsafe {
+            access.write(self.address.address, self.address.offset + 0x08, reg);
+        }
+    }
+
+    /// Read-modify-write the Device Control register. The Device Status register shares its dword, but has
+    /// write-1-to-clear bits, so it is written as zero.
+    fn update_device_control<F>(&self, access: impl ConfigRegionAccess, f: F)
+    where
+        F: FnOnce(&mut u32),
+    {
+        let mut reg = unsafe { access.read(self.address.address, self.address.offset + 0x08) };
+        reg.set_bits(16..32, 0);
+        f(&mut reg);
+        unsafe {
+            access.write(self.address.address, self.address.offset + 0x08, reg);
+        }
+    }
+
+    /// Whether the function implements the Link registers. These are implemented by all functions
+    /// except Root Complex Integrated Endpoints and Root Complex Event Collectors, which do not have a
+    /// link.
+    pub fn has_link_registers(&self) -> bool {
+        !matches!(
+            self.device_port_type,
+            DevicePortType::RootComplexIntegratedEndpoint | DevicePortType::RootComplexEventCollector
+        )
+    }
+
+    /// Read the Link Control register. Returns `None` if the function does not have a link.
+    pub fn link_control(&self, access: impl ConfigRegionAccess) -> Option<LinkControl> {
+        if !self.has_link_registers() {
+            return None;
+        }
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x10) };
+        Some(LinkControl(reg.get_bits(0..16) as u16))
+    }
+
+    /// Write the Link Control register. The Link Status register shares the same dword, but has
+    /// write-1-to-clear bits, so it is written as zero.
+    pub fn set_link_control(
+        &self,
+        control: LinkControl,
+        access: impl ConfigRegionAccess,
+    ) -> Result<(), RegisterNotPresent> {
+        if !self.has_link_registers() {
+            return Err(RegisterNotPresent);
+        }
+        unsafe {
+            access.write(self.address.address, self.address.offset + 0x10, control.0 as u32);
+        }
+        Ok(())
+    }
+
+    /// Whether the Data Link Layer of the link is active, which is how software detects that a device
+    /// has appeared after hot-plug. Returns `None` if the function does not have a link, or does not
+    /// support reporting this (as indicated by the Data Link Layer Link Active Reporting Capable bit of the
+    /// Link Capabilities register).
+    pub fn link_active(&self, access: impl ConfigRegionAccess) -> Option<bool> {
+        if !self.has_link_registers() {
+            return None;
+        }
+        let capabilities = unsafe { access.read(self.address.address, self.address.offset + 0x0c) };
+        if !capabilities.get_bit(20) {
+            return None;
+        }
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x10) };
+        Some(reg.get_bit(29))
+    }
+
+    /// The maximum speed the link supports. Returns `None` if the function does not have a link, or reports
+    /// a reserved speed.
+    pub fn max_link_speed(&self, access: impl ConfigRegionAccess) -> Option<LinkSpeed> {
+        if !self.has_link_registers() {
+            return None;
+        }
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x0c) };
+        LinkSpeed::try_from(reg.get_bits(0..4) as u8).ok()
+    }
+
+    /// The maximum number of lanes the link supports. Returns `None` if the function does not have a link.
+    pub fn max_link_width(&self, access: impl ConfigRegionAccess) -> Option<u8> {
+        if !self.has_link_registers() {
+            return None;
+        }
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x0c) };
+        Some(reg.get_bits(4..10) as u8)
+    }
+
+    /// The speed the link is currently running at. Returns `None` if the function does not have a link, or
+    /// reports a reserved speed.
+    pub fn current_link_speed(&self, access: impl ConfigRegionAccess) -> Option<LinkSpeed> {
+        if !self.has_link_registers() {
+            return None;
+        }
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x10) };
+        LinkSpeed::try_from(reg.get_bits(16..20) as u8).ok()
+    }
+
+    /// The number of lanes the link was negotiated to use. Returns `None` if the function does not have a
+    /// link.
+    pub fn negotiated_link_width(&self, access: impl ConfigRegionAccess) -> Option<u8> {
+        if !self.has_link_registers() {
+            return None;
+        }
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x10) };
+        Some(reg.get_bits(20..26) as u8)
+    }
+
+    /// Whether the link has trained to a lower speed or fewer lanes than it supports. Returns `None` if the
+    /// function does not have a link, or reports a reserved speed.
+    ///
+    /// The maximum speed and width are those of this end of the link - a link will also be degraded if the
+    /// other end supports less.
+    pub fn link_degraded(&self, access: impl ConfigRegionAccess) -> Option<bool> {
+        let max_speed = self.max_link_speed(&access)?;
+        let max_width = self.max_link_width(&access)?;
+        let current_speed = self.current_link_speed(&access)?;
+        let negotiated_width = self.negotiated_link_width(&access)?;
+        Some(current_speed < max_speed || negotiated_width < max_width)
+    }
+
+    /// Whether the link of this port is connected to a slot. Only valid for Root Ports and Downstream
+    /// Switch Ports, and determines whether the slot registers are implemented.
+    pub fn slot_implemented(&self) -> bool {
+        self.slot_implemented
+            && matches!(self.device_port_type, DevicePortType::RootPort | DevicePortType::DownstreamSwitchPort)
+    }
+
+    /// Read the Slot Capabilities register. Returns `None` if this port does not implement a slot.
+    pub fn slot_capabilities(&self, access: impl ConfigRegionAccess) -> Option<SlotCapabilities> {
+        if !self.slot_implemented() {
+            return None;
+        }
+        Some(SlotCapabilities(unsafe { access.read(self.address.address, self.address.offset + 0x14) }))
+    }
+
+    /// The maximum power that can be drawn by a device in this port's slot, as a value and scale (see
+    /// [`SlotCapabilities::slot_power_limit`]). Returns `None` if this port does not implement a slot.
+    pub fn slot_power_limit(&self, access: impl ConfigRegionAccess) -> Option<(u8, PowerLimitScale)> {
+        self.slot_capabilities(access).map(|capabilities| capabilities.slot_power_limit())
+    }
+
+    /// Read the Slot Control register. Returns `None` if this port does not implement a slot.
+    pub fn slot_control(&self, access: impl ConfigRegionAccess) -> Option<SlotControl> {
+        if !self.slot_implemented() {
+            return None;
+        }
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x18) };
+        Some(SlotControl(reg.get_bits(0..16) as u16))
+    }
+
+    /// Write the Slot Control register. The Slot Status register shares the same dword, but its bits are
+    /// write-1-to-clear, so this will not acknowledge any pending slot events.
+    pub fn set_slot_control(
+        &self,
+        control: SlotControl,
+        access: impl ConfigRegionAccess,
+    ) -> Result<(), RegisterNotPresent> {
+        if !self.slot_implemented() {
+            return Err(RegisterNotPresent);
+        }
+        unsafe {
+            access.write(self.address.address, self.address.offset + 0x18, control.0 as u32);
+        }
+        Ok(())
+    }
+
+    /// Read the Slot Status register. Returns `None` if this port does not implement a slot.
+    pub fn slot_status(&self, access: impl ConfigRegionAccess) -> Option<SlotStatus> {
+        if !self.slot_implemented() {
+            return None;
+        }
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x18) };
+        Some(SlotStatus::from_bits_retain(reg.get_bits(16..32) as u16))
+    }
+
+    /// Acknowledge the slot events in `mask` by writing `1` to them in the Slot Status register,
+    /// preserving the contents of the Slot Control register.
+    pub fn clear_slot_status(
+        &self,
+        mask: SlotStatus,
+        access: impl ConfigRegionAccess,
+    ) -> Result<(), RegisterNotPresent> {
+        if !self.slot_implemented() {
+            return Err(RegisterNotPresent);
+        }
+        let mut reg = unsafe { access.read(self.address.address, self.address.offset + 0x18) };
+        reg.set_bits(16..32, mask.bits() as u32);
+        unsafe {
+            access.write(self.address.address, self.address.offset + 0x18, reg);
+        }
+        Ok(())
+    }
+
+    /// Whether the function implements the second-generation registers (Device Capabilities 2, Device
+    /// Control 2, etc.), which were added in version `2` of the capability structure.
+    fn has_version2_registers(&self, access: impl ConfigRegionAccess) -> bool {
+        self.version(access) >= 2
+    }
+
+    /// Read the Device Capabilities 2 register. Returns `None` if the capability structure is older than
+    /// version `2`.
+    pub fn device_capabilities2(&self, access: impl ConfigRegionAccess) -> Option<DeviceCapabilities2> {
+        if !self.has_version2_registers(&access) {
+            return None;
+        }
+        Some(DeviceCapabilities2(unsafe { access.read(self.address.address, self.address.offset + 0x24) }))
+    }
+
+    /// Read the Device Control 2 register. Returns `None` if the capability structure is older than
+    /// version `2`.
+    pub fn device_control2(&self, access: impl ConfigRegionAccess) -> Option<DeviceControl2> {
+        if !self.has_version2_registers(&access) {
+            return None;
+        }
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x28) };
+        Some(DeviceControl2(reg.get_bits(0..16) as u16))
+    }
+
+    /// Write the Device Control 2 register. The Device Status 2 register shares the same dword, but is
+    /// reserved, so it is written as zero.
+    pub fn set_device_control2(
+        &self,
+        control: DeviceControl2,
+        access: impl ConfigRegionAccess,
+    ) -> Result<(), RegisterNotPresent> {
+        if !self.has_version2_registers(&access) {
+            return Err(RegisterNotPresent);
+        }
+        unsafe {
+            access.write(self.address.address, self.address.offset + 0x28, control.0 as u32);
+        }
+        Ok(())
+    }
+
+    fn has_root_registers(&self) -> bool {
+        matches!(self.device_port_type, DevicePortType::RootPort | DevicePortType::RootComplexEventCollector)
+    }
+
+    /// Read the Root Control register. Returns `None` if this function is not a Root Port or Root Complex
+    /// Event Collector.
+    pub fn root_control(&self, access: impl ConfigRegionAccess) -> Option<RootControl> {
+        if !self.has_root_registers() {
+            return None;
+        }
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x1c) };
+        Some(RootControl::from_bits_retain(reg.get_bits(0..16) as u16))
+    }
+
+    /// Write the Root Control register.
+    pub fn set_root_control(
+        &self,
+        control: RootControl,
+        access: impl ConfigRegionAccess,
+    ) -> Result<(), RegisterNotPresent> {
+        if !self.has_root_registers() {
+            return Err(RegisterNotPresent);
+        }
+        let mut reg = unsafe { access.read(self.address.address, self.address.offset + 0x1c) };
+        reg.set_bits(0..16, control.bits() as u32);
+        unsafe {
+            access.write(self.address.address, self.address.offset + 0x1c, reg);
+        }
+        Ok(())
+    }
+
+    /// Read the Root Status register. Returns `None` if this function is not a Root Port or Root Complex
+    /// Event Collector.
+    pub fn root_status(&self, access: impl ConfigRegionAccess) -> Option<RootStatus> {
+        if !self.has_root_registers() {
+            return None;
+        }
+        Some(RootStatus(unsafe { access.read(self.address.address, self.address.offset + 0x20) }))
+    }
+
+    /// Acknowledge the PME recorded in the Root Status register. If another PME is pending, the Root
+    /// Status register will then be updated with its requester.
+    pub fn clear_pme_status(&self, access: impl ConfigRegionAccess) -> Result<(), RegisterNotPresent> {
+        if !self.has_root_registers() {
+            return Err(RegisterNotPresent);
+        }
+        unsafe {
+            access.write(self.address.address, self.address.offset + 0x20, 1 << 16);
+        }
+        Ok(())
+    }
+}
+
+bitflags::bitflags! {
+    /// The Device Status register of a PCI Express function. The error detected bits are
+    /// write-1-to-clear, and can be acknowledged with [`PciExpressCapability::clear_device_status`].
+    #[repr(transparent)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct DeviceStatus: u16 {
+        const CORRECTABLE_ERROR_DETECTED = 1 << 0;
+        const NON_FATAL_ERROR_DETECTED = 1 << 1;
+        const FATAL_ERROR_DETECTED = 1 << 2;
+        const UNSUPPORTED_REQUEST_DETECTED = 1 << 3;
+        const AUX_POWER_DETECTED = 1 << 4;
+        const TRANSACTIONS_PENDING = 1 << 5;
+        const EMERGENCY_POWER_REDUCTION_DETECTED = 1 << 6;
+        const _ = !0;
+    }
+}
+
+bitflags::bitflags! {
+    /// The Root Control register of a Root Port or Root Complex Event Collector.
+    #[repr(transparent)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct RootControl: u16 {
+        const SYSTEM_ERROR_ON_CORRECTABLE_ERROR_ENABLE = 1 << 0;
+        const SYSTEM_ERROR_ON_NON_FATAL_ERROR_ENABLE = 1 << 1;
+        const SYSTEM_ERROR_ON_FATAL_ERROR_ENABLE = 1 << 2;
+        const PME_INTERRUPT_ENABLE = 1 << 3;
+        const CRS_SOFTWARE_VISIBILITY_ENABLE = 1 << 4;
+        const _ = !0;
+    }
+}
+
+/// The Root Status register of a Root Port or Root Complex Event Collector.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct RootStatus(u32);
+
+impl RootStatus {
+    /// The requester ID of the function that most recently sent a PME. Only valid if
+    /// [`RootStatus::pme_status`] is `true`.
+    pub fn pme_requester_id(&self) -> u16 {
+        self.0.get_bits(0..16) as u16
+    }
+
+    /// Whether a PME has been received from the function identified by [`RootStatus::pme_requester_id`].
+    pub fn pme_status(&self) -> bool {
+        self.0.get_bit(16)
+    }
+
+    /// Whether another PME is pending while [`RootStatus::pme_status`] is set.
+    pub fn pme_pending(&self) -> bool {
+        self.0.get_bit(17)
+    }
+}
+
+impl Debug for RootStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RootStatus")
+            .field("pme_requester_id", &self.pme_requester_id())
+            .field("pme_status", &self.pme_status())
+            .field("pme_pending", &self.pme_pending())
+            .finish()
+    }
+}
+
+/// The speed of a PCI Express link, in gigatransfers per second per lane.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum LinkSpeed {
+    /// 2.5 GT/s (PCI Express 1.x)
+    Gt2_5 = 0b0001,
+    /// 5.0 GT/s (PCI Express 2.x)
+    Gt5 = 0b0010,
+    /// 8.0 GT/s (PCI Express 3.x)
+    Gt8 = 0b0011,
+    /// 16.0 GT/s (PCI Express 4.x)
+    Gt16 = 0b0100,
+    /// 32.0 GT/s (PCI Express 5.x)
+    Gt32 = 0b0101,
+    /// 64.0 GT/s (PCI Express 6.x)
+    Gt64 = 0b0110,
+}
+
+impl TryFrom<u8> for LinkSpeed {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0b0001 => Ok(LinkSpeed::Gt2_5),
+            0b0010 => Ok(LinkSpeed::Gt5),
+            0b0011 => Ok(LinkSpeed::Gt8),
+            0b0100 => Ok(LinkSpeed::Gt16),
+            0b0101 => Ok(LinkSpeed::Gt32),
+            0b0110 => Ok(LinkSpeed::Gt64),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Which Active State Power Management (ASPM) link states are enabled on a link.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AspmControl {
+    Disabled = 0b00,
+    L0s = 0b01,
+    L1 = 0b10,
+    L0sAndL1 = 0b11,
+}
+
+impl From<u8> for AspmControl {
+    fn from(value: u8) -> Self {
+        match value & 0b11 {
+            0b01 => AspmControl::L0s,
+            0b10 => AspmControl::L1,
+            0b11 => AspmControl::L0sAndL1,
+            _ => AspmControl::Disabled,
+        }
+    }
+}
+
+/// The Link Control register of a PCI Express function.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct LinkControl(u16);
+
+impl LinkControl {
+    pub fn new(value: u16) -> Self {
+        LinkControl(value)
+    }
+
+    pub fn bits(&self) -> u16 {
+        self.0
+    }
+
+    pub fn aspm_control(&self) -> AspmControl {
+        AspmControl::from(self.0.get_bits(0..2) as u8)
+    }
+
+    pub fn set_aspm_control(&mut self, control: AspmControl) {
+        self.0.set_bits(0..2, control as u16);
+    }
+
+    /// Whether the link is disabled. Only meaningful for Downstream Ports.
+    pub fn link_disable(&self) -> bool {
+        self.0.get_bit(4)
+    }
+
+    pub fn set_link_disable(&mut self, disable: bool) {
+        self.0.set_bit(4, disable);
+    }
+
+    /// Whether the components at both ends of the link use a distributed common reference clock.
+    pub fn common_clock_configuration(&self) -> bool {
+        self.0.get_bit(6)
+    }
+
+    pub fn set_common_clock_configuration(&mut self, common: bool) {
+        self.0.set_bit(6, common);
+    }
+
+    /// Whether extended synchronization sequences are transmitted, to give external devices monitoring
+    /// the link time to achieve bit and symbol lock.
+    pub fn extended_synch(&self) -> bool {
+        self.0.get_bit(7)
+    }
+
+    pub fn set_extended_synch(&mut self, extended: bool) {
+        self.0.set_bit(7, extended);
+    }
+}
+
+impl Debug for LinkControl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LinkControl")
+            .field("aspm_control", &self.aspm_control())
+            .field("link_disable", &self.link_disable())
+            .field("common_clock_configuration", &self.common_clock_configuration())
+            .field("extended_synch", &self.extended_synch())
+            .finish()
+    }
+}
+
+/// The Slot Capabilities register of a PCI Express port that implements a slot.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SlotCapabilities(u32);
+
+impl SlotCapabilities {
+    pub fn attention_button_present(&self) -> bool {
+        self.0.get_bit(0)
+    }
+
+    pub fn power_controller_present(&self) -> bool {
+        self.0.get_bit(1)
+    }
+
+    pub fn mrl_sensor_present(&self) -> bool {
+        self.0.get_bit(2)
+    }
+
+    pub fn attention_indicator_present(&self) -> bool {
+        self.0.get_bit(3)
+    }
+
+    pub fn power_indicator_present(&self) -> bool {
+        self.0.get_bit(4)
+    }
+
+    /// Whether an adapter in this slot may be removed without prior notification.
+    pub fn hot_plug_surprise(&self) -> bool {
+        self.0.get_bit(5)
+    }
+
+    pub fn hot_plug_capable(&self) -> bool {
+        self.0.get_bit(6)
+    }
+
+    /// The maximum power that can be drawn by a device in the slot. The limit in watts is the value
+    /// multiplied by the scale.
+    pub fn slot_power_limit(&self) -> (u8, PowerLimitScale) {
+        (self.0.get_bits(7..15) as u8, PowerLimitScale::from(self.0.get_bits(15..17) as u8))
+    }
+
+    pub fn electromechanical_interlock_present(&self) -> bool {
+        self.0.get_bit(17)
+    }
+
+    /// If `true`, the slot does not generate software notification when an issued command is
+    /// completed.
+    pub fn no_command_completed_support(&self) -> bool {
+        self.0.get_bit(18)
+    }
+
+    /// The chassis-unique number of the physical slot.
+    pub fn physical_slot_number(&self) -> u16 {
+        self.0.get_bits(19..32) as u16
+    }
+}
+
+impl Debug for SlotCapabilities {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SlotCapabilities")
+            .field("attention_button_present", &self.attention_button_present())
+            .field("power_controller_present", &self.power_controller_present())
+            .field("mrl_sensor_present", &self.mrl_sensor_present())
+            .field("attention_indicator_present", &self.attention_indicator_present())
+            .field("power_indicator_present", &self.power_indicator_present())
+            .field("hot_plug_surprise", &self.hot_plug_surprise())
+            .field("hot_plug_capable", &self.hot_plug_capable())
+            .field("slot_power_limit", &self.slot_power_limit())
+            .field("electromechanical_interlock_present", &self.electromechanical_interlock_present())
+            .field("no_command_completed_support", &self.no_command_completed_support())
+            .field("physical_slot_number", &self.physical_slot_number())
+            .finish()
+    }
+}
+
+/// The Device Capabilities 2 register of a PCI Express function.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct DeviceCapabilities2(u32);
+
+impl DeviceCapabilities2 {
+    /// The Completion Timeout ranges the function supports, as a bitmask of ranges `A` (bit `0`) to `D`
+    /// (bit `3`). If `0`, the function does not support programming its Completion Timeout.
+    pub fn completion_timeout_ranges(&self) -> u8 {
+        self.0.get_bits(0..4) as u8
+    }
+
+    pub fn completion_timeout_disable_supported(&self) -> bool {
+        self.0.get_bit(4)
+    }
+
+    /// Whether a Downstream Port supports forwarding requests with ARI.
+    pub fn ari_forwarding_supported(&self) -> bool {
+        self.0.get_bit(5)
+    }
+
+    /// Whether the port supports routing AtomicOp requests.
+    pub fn atomic_op_routing_supported(&self) -> bool {
+        self.0.get_bit(6)
+    }
+
+    pub fn atomic_op_32bit_completer_supported(&self) -> bool {
+        self.0.get_bit(7)
+    }
+
+    pub fn atomic_op_64bit_completer_supported(&self) -> bool {
+        self.0.get_bit(8)
+    }
+
+    pub fn cas_128bit_completer_supported(&self) -> bool {
+        self.0.get_bit(9)
+    }
+
+    /// Whether the function supports Latency Tolerance Reporting.
+    pub fn ltr_mechanism_supported(&self) -> bool {
+        self.0.get_bit(11)
+    }
+
+    pub fn ten_bit_tag_completer_supported(&self) -> bool {
+        self.0.get_bit(16)
+    }
+
+    pub fn ten_bit_tag_requester_supported(&self) -> bool {
+        self.0.get_bit(17)
+    }
+}
+
+impl Debug for DeviceCapabilities2 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeviceCapabilities2")
+            .field("completion_timeout_ranges", &self.completion_timeout_ranges())
+            .field("completion_timeout_disable_supported", &self.completion_timeout_disable_supported())
+            .field("ari_forwarding_supported", &self.ari_forwarding_supported())
+            .field("atomic_op_routing_supported", &self.atomic_op_routing_supported())
+            .field("atomic_op_32bit_completer_supported", &self.atomic_op_32bit_completer_supported())
+            .field("atomic_op_64bit_completer_supported", &self.atomic_op_64bit_completer_supported())
+            .field("cas_128bit_completer_supported", &self.cas_128bit_completer_supported())
+            .field("ltr_mechanism_supported", &self.ltr_mechanism_supported())
+            .field("ten_bit_tag_completer_supported", &self.ten_bit_tag_completer_supported())
+            .field("ten_bit_tag_requester_supported", &self.ten_bit_tag_requester_supported())
+            .finish()
+    }
+}
+
+/// The Device Control 2 register of a PCI Express function.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct DeviceControl2(u16);
+
+impl DeviceControl2 {
+    pub fn new(value: u16) -> Self {
+        DeviceControl2(value)
+    }
+
+    pub fn bits(&self) -> u16 {
+        self.0
+    }
+
+    /// The encoded Completion Timeout value. `0` selects the default range of 50us to 50ms; other values
+    /// must be within one of the ranges reported by [`DeviceCapabilities2::completion_timeout_ranges`].
+    pub fn completion_timeout_value(&self) -> u8 {
+        self.0.get_bits(0..4) as u8
+    }
+
+    pub fn set_completion_timeout_value(&mut self, value: u8) {
+        self.0.set_bits(0..4, value.get_bits(0..4) as u16);
+    }
+
+    pub fn completion_timeout_disable(&self) -> bool {
+        self.0.get_bit(4)
+    }
+
+    pub fn set_completion_timeout_disable(&mut self, disable: bool) {
+        self.0.set_bit(4, disable);
+    }
+
+    pub fn ari_forwarding_enable(&self) -> bool {
+        self.0.get_bit(5)
+    }
+
+    pub fn set_ari_forwarding_enable(&mut self, enable: bool) {
+        self.0.set_bit(5, enable);
+    }
+
+    pub fn atomic_op_requester_enable(&self) -> bool {
+        self.0.get_bit(6)
+    }
+
+    pub fn set_atomic_op_requester_enable(&mut self, enable: bool) {
+        self.0.set_bit(6, enable);
+    }
+
+    /// Whether AtomicOp requests are blocked from being forwarded out of this Egress Port.
+    pub fn atomic_op_egress_blocking(&self) -> bool {
+        self.0.get_bit(7)
+    }
+
+    pub fn set_atomic_op_egress_blocking(&mut self, blocking: bool) {
+        self.0.set_bit(7, blocking);
+    }
+
+    pub fn ltr_mechanism_enable(&self) -> bool {
+        self.0.get_bit(10)
+    }
+
+    pub fn set_ltr_mechanism_enable(&mut self, enable: bool) {
+        self.0.set_bit(10, enable);
+    }
+
+    pub fn ten_bit_tag_requester_enable(&self) -> bool {
+        self.0.get_bit(12)
+    }
+
+    pub fn set_ten_bit_tag_requester_enable(&mut self, enable: bool) {
+        self.0.set_bit(12, enable);
+    }
+}
+
+impl Debug for DeviceControl2 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeviceControl2")
+            .field("completion_timeout_value", &self.completion_timeout_value())
+            .field("completion_timeout_disable", &self.completion_timeout_disable())
+            .field("ari_forwarding_enable", &self.ari_forwarding_enable())
+            .field("atomic_op_requester_enable", &self.atomic_op_requester_enable())
+            .field("atomic_op_egress_blocking", &self.atomic_op_egress_blocking())
+            .field("ltr_mechanism_enable", &self.ltr_mechanism_enable())
+            .field("ten_bit_tag_requester_enable", &self.ten_bit_tag_requester_enable())
+            .finish()
+    }
+}
+
+/// The scale of a slot power limit, as reported by [`SlotCapabilities::slot_power_limit`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PowerLimitScale {
+    /// 1.0x
+    One = 0b00,
+    /// 0.1x
+    OneTenth = 0b01,
+    /// 0.01x
+    OneHundredth = 0b10,
+    /// 0.001x
+    OneThousandth = 0b11,
+}
+
+impl PowerLimitScale {
+    /// Convert a power limit value with this scale to milliwatts
+    pub fn milliwatts(&self, value: u8) -> u32 {
+        let value = value as u32;
+        match self {
+            PowerLimitScale::One => value * 1000,
+            PowerLimitScale::OneTenth => value * 100,
+            PowerLimitScale::OneHundredth => value * 10,
+            PowerLimitScale::OneThousandth => value,
+        }
+    }
+}
+
+impl From<u8> for PowerLimitScale {
+    fn from(value: u8) -> Self {
+        match value.get_bits(0..2) {
+            0b00 => PowerLimitScale::One,
+            0b01 => PowerLimitScale::OneTenth,
+            0b10 => PowerLimitScale::OneHundredth,
+            _ => PowerLimitScale::OneThousandth,
+        }
+    }
+}
+
+/// The state of an Attention or Power Indicator, as controlled through the Slot Control register.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum IndicatorState {
+    Reserved = 0b00,
+    On = 0b01,
+    Blink = 0b10,
+    Off = 0b11,
+}
+
+impl From<u8> for IndicatorState {
+    fn from(value: u8) -> Self {
+        match value & 0b11 {
+            0b01 => IndicatorState::On,
+            0b10 => IndicatorState::Blink,
+            0b11 => IndicatorState::Off,
+            _ => IndicatorState::Reserved,
+        }
+    }
+}
+
+/// The Slot Control register of a PCI Express port that implements a slot.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SlotControl(u16);
+
+impl SlotControl {
+    pub fn new(value: u16) -> Self {
+        SlotControl(value)
+    }
+
+    pub fn bits(&self) -> u16 {
+        self.0
+    }
+
+    pub fn attention_button_pressed_enable(&self) -> bool {
+        self.0.get_bit(0)
+    }
+
+    pub fn set_attention_button_pressed_enable(&mut self, enable: bool) {
+        self.0.set_bit(0, enable);
+    }
+
+    pub fn power_fault_detected_enable(&self) -> bool {
+        self.0.get_bit(1)
+    }
+
+    pub fn set_power_fault_detected_enable(&mut self, enable: bool) {
+        self.0.set_bit(1, enable);
+    }
+
+    pub fn mrl_sensor_changed_enable(&self) -> bool {
+        self.0.get_bit(2)
+    }
+
+    pub fn set_mrl_sensor_changed_enable(&mut self, enable: bool) {
+        self.0.set_bit(2, enable);
+    }
+
+    pub fn presence_detect_changed_enable(&self) -> bool {
+        self.0.get_bit(3)
+    }
+
+    pub fn set_presence_detect_changed_enable(&mut self, enable: bool) {
+        self.0.set_bit(3, enable);
+    }
+
+    pub fn command_completed_interrupt_enable(&self) -> bool {
+        self.0.get_bit(4)
+    }
+
+    pub fn set_command_completed_interrupt_enable(&mut self, enable: bool) {
+        self.0.set_bit(4, enable);
+    }
+
+    pub fn hot_plug_interrupt_enable(&self) -> bool {
+        self.0.get_bit(5)
+    }
+
+    pub fn set_hot_plug_interrupt_enable(&mut self, enable: bool) {
+        self.0.set_bit(5, enable);
+    }
+
+    pub fn attention_indicator(&self) -> IndicatorState {
+        IndicatorState::from(self.0.get_bits(6..8) as u8)
+    }
+
+    pub fn set_attention_indicator(&mut self, state: IndicatorState) {
+        self.0.set_bits(6..8, state as u16);
+    }
+
+    pub fn power_indicator(&self) -> IndicatorState {
+        IndicatorState::from(self.0.get_bits(8..10) as u8)
+    }
+
+    pub fn set_power_indicator(&mut self, state: IndicatorState) {
+        self.0.set_bits(8..10, state as u16);
+    }
+
+    /// Whether the Power Controller is instructed to supply power to the slot. Note that the
+    /// register bit itself is set to `1` to turn the power *off*.
+    pub fn power_controller_on(&self) -> bool {
+        !self.0.get_bit(10)
+    }
+
+    pub fn set_power_controller_on(&mut self, on: bool) {
+        self.0.set_bit(10, !on);
+    }
+
+    pub fn data_link_layer_state_changed_enable(&self) -> bool {
+        self.0.get_bit(12)
+    }
+
+    pub fn set_data_link_layer_state_changed_enable(&mut self, enable: bool) {
+        self.0.set_bit(12, enable);
+    }
+}
+
+impl Debug for SlotControl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SlotControl")
+            .field("attention_button_pressed_enable", &self.attention_button_pressed_enable())
+            .field("power_fault_detected_enable", &self.power_fault_detected_enable())
+            .field("mrl_sensor_changed_enable", &self.mrl_sensor_changed_enable())
+            .field("presence_detect_changed_enable", &self.presence_detect_changed_enable())
+            .field("command_completed_interrupt_enable", &self.command_completed_interrupt_enable())
+            .field("hot_plug_interrupt_enable", &self.hot_plug_interrupt_enable())
+            .field("attention_indicator", &self.attention_indicator())
+            .field("power_indicator", &self.power_indicator())
+            .field("power_controller_on", &self.power_controller_on())
+            .field("data_link_layer_state_changed_enable", &self.data_link_layer_state_changed_enable())
+            .finish()
+    }
+}
+
+bitflags::bitflags! {
+    /// The Slot Status register of a PCI Express port that implements a slot. The event bits are
+    /// write-1-to-clear, and can be acknowledged with [`PciExpressCapability::clear_slot_status`].
+    #[repr(transparent)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct SlotStatus: u16 {
+        const ATTENTION_BUTTON_PRESSED = 1 << 0;
+        const POWER_FAULT_DETECTED = 1 << 1;
+        const MRL_SENSOR_CHANGED = 1 << 2;
+        const PRESENCE_DETECT_CHANGED = 1 << 3;
+        const COMMAND_COMPLETED = 1 << 4;
+        const MRL_SENSOR_STATE = 1 << 5;
+        const PRESENCE_DETECT_STATE = 1 << 6;
+        const ELECTROMECHANICAL_INTERLOCK_STATUS = 1 << 7;
+        const DATA_LINK_LAYER_STATE_CHANGED = 1 << 8;
+        const _ = !0;
+    }
+}
@@ -0,0 +1,221 @@
+use super::PciCapabilityAddress;
+use crate::ConfigRegionAccess;
+use bit_field::BitField;
+use core::fmt::{self, Debug, Formatter};
+
+/// The maximum byte count the device uses when initiating a sequence with one of the burst memory read
+/// commands.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum MaxMemoryReadByteCount {
+    Bytes512 = 0b00,
+    Bytes1024 = 0b01,
+    Bytes2048 = 0b10,
+    Bytes4096 = 0b11,
+}
+
+impl From<u8> for MaxMemoryReadByteCount {
+    fn from(value: u8) -> Self {
+        match value & 0b11 {
+            0b00 => MaxMemoryReadByteCount::Bytes512,
+            0b01 => MaxMemoryReadByteCount::Bytes1024,
+            0b10 => MaxMemoryReadByteCount::Bytes2048,
+            _ => MaxMemoryReadByteCount::Bytes4096,
+        }
+    }
+}
+
+/// The maximum number of Split Transactions the device is permitted to have outstanding at one time.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum MaxOutstandingSplitTransactions {
+    One = 0b000,
+    Two = 0b001,
+    Three = 0b010,
+    Four = 0b011,
+    Eight = 0b100,
+    Twelve = 0b101,
+    Sixteen = 0b110,
+    ThirtyTwo = 0b111,
+}
+
+impl From<u8> for MaxOutstandingSplitTransactions {
+    fn from(value: u8) -> Self {
+        match value & 0b111 {
+            0b000 => MaxOutstandingSplitTransactions::One,
+            0b001 => MaxOutstandingSplitTransactions::Two,
+            0b010 => MaxOutstandingSplitTransactions::Three,
+            0b011 => MaxOutstandingSplitTransactions::Four,
+            0b100 => MaxOutstandingSplitTransactions::Eight,
+            0b101 => MaxOutstandingSplitTransactions::Twelve,
+            0b110 => MaxOutstandingSplitTransactions::Sixteen,
+            _ => MaxOutstandingSplitTransactions::ThirtyTwo,
+        }
+    }
+}
+
+/// The PCI-X capability of a device with a Type-0 header. PCI-X bridges use a different layout, which is
+/// not decoded by this type.
+#[derive(Clone, Copy, Debug)]
+pub struct PciXCapability {
+    pub(super) address: PciCapabilityAddress,
+}
+
+impl PciXCapability {
+    pub(crate) fn new(address: PciCapabilityAddress) -> PciXCapability {
+        PciXCapability { address }
+    }
+
+    /// Read the PCI-X Command register
+    pub fn command(&self, access: impl ConfigRegionAccess) -> u16 {
+        let reg = unsafe { access.read(self.address.address, self.address.offset) };
+        reg.get_bits(16..32) as u16
+    }
+
+    /// Write the PCI-X Command register
+    pub fn set_command(&self, command: u16, access: impl ConfigRegionAccess) {
+        let mut reg = unsafe { access.read(self.address.address, self.address.offset) };
+        reg.set_bits(16..32, command as u32);
+        unsafe { access.write(self.address.address, self.address.offset, reg) };
+    }
+
+    pub fn data_parity_error_recovery_enabled(&self, access: impl ConfigRegionAccess) -> bool {
+        self.command(access).get_bit(0)
+    }
+
+    pub fn set_data_parity_error_recovery_enabled(&self, enabled: bool, access: impl ConfigRegionAccess) {
+        let mut command = self.command(&access);
+        command.set_bit(0, enabled);
+        self.set_command(command, access);
+    }
+
+    pub fn relaxed_ordering_enabled(&self, access: impl ConfigRegionAccess) -> bool {
+        self.command(access).get_bit(1)
+    }
+
+    pub fn set_relaxed_ordering_enabled(&self, enabled: bool, access: impl ConfigRegionAccess) {
+        let mut command = self.command(&access);
+        command.set_bit(1, enabled);
+        self.set_command(command, access);
+    }
+
+    pub fn max_memory_read_byte_count(&self, access: impl ConfigRegionAccess) -> MaxMemoryReadByteCount {
+        MaxMemoryReadByteCount::from(self.command(access).get_bits(2..4) as u8)
+    }
+
+    /// Set the maximum memory read byte count. This should not be larger than
+    /// [`PciXStatus::designed_max_memory_read_byte_count`].
+    pub fn set_max_memory_read_byte_count(&self, count: MaxMemoryReadByteCount, access: impl ConfigRegionAccess) {
+        let mut command = self.command(&access);
+        command.set_bits(2..4, count as u16);
+        self.set_command(command, access);
+    }
+
+    pub fn max_outstanding_split_transactions(
+        &self,
+        access: impl ConfigRegionAccess,
+    ) -> MaxOutstandingSplitTransactions {
+        MaxOutstandingSplitTransactions::from(self.command(access).get_bits(4..7) as u8)
+    }
+
+    /// Set the maximum number of outstanding Split Transactions. This should not be larger than
+    /// [`PciXStatus::designed_max_outstanding_split_transactions`].
+    pub fn set_max_outstanding_split_transactions(
+        &self,
+        count: MaxOutstandingSplitTransactions,
+        access: impl ConfigRegionAccess,
+    ) {
+        let mut command = self.command(&access);
+        command.set_bits(4..7, count as u16);
+        self.set_command(command, access);
+    }
+
+    /// Read the PCI-X Status register
+    pub fn status(&self, access: impl ConfigRegionAccess) -> PciXStatus {
+        PciXStatus(unsafe { access.read(self.address.address, self.address.offset + 0x04) })
+    }
+}
+
+/// The PCI-X Status register of a device with a Type-0 header.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct PciXStatus(u32);
+
+impl PciXStatus {
+    /// The function number of the device, as captured from its last configuration write
+    pub fn function_number(&self) -> u8 {
+        self.0.get_bits(0..3) as u8
+    }
+
+    /// The device number of the device, as captured from its last configuration write
+    pub fn device_number(&self) -> u8 {
+        self.0.get_bits(3..8) as u8
+    }
+
+    /// The bus number of the device, as captured from its last configuration write
+    pub fn bus_number(&self) -> u8 {
+        self.0.get_bits(8..16) as u8
+    }
+
+    pub fn is_64bit(&self) -> bool {
+        self.0.get_bit(16)
+    }
+
+    pub fn capable_133mhz(&self) -> bool {
+        self.0.get_bit(17)
+    }
+
+    pub fn split_completion_discarded(&self) -> bool {
+        self.0.get_bit(18)
+    }
+
+    pub fn unexpected_split_completion(&self) -> bool {
+        self.0.get_bit(19)
+    }
+
+    /// Whether the device is a bridge-like device that can initiate transactions on behalf of other
+    /// devices.
+    pub fn complex_device(&self) -> bool {
+        self.0.get_bit(20)
+    }
+
+    pub fn designed_max_memory_read_byte_count(&self) -> MaxMemoryReadByteCount {
+        MaxMemoryReadByteCount::from(self.0.get_bits(21..23) as u8)
+    }
+
+    pub fn designed_max_outstanding_split_transactions(&self) -> MaxOutstandingSplitTransactions {
+        MaxOutstandingSplitTransactions::from(self.0.get_bits(23..26) as u8)
+    }
+
+    pub fn received_split_completion_error(&self) -> bool {
+        self.0.get_bit(29)
+    }
+
+    pub fn capable_266mhz(&self) -> bool {
+        self.0.get_bit(30)
+    }
+
+    pub fn capable_533mhz(&self) -> bool {
+        self.0.get_bit(31)
+    }
+}
+
+impl Debug for PciXStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PciXStatus")
+            .field("function_number", &self.function_number())
+            .field("device_number", &self.device_number())
+            .field("bus_number", &self.bus_number())
+            .field("is_64bit", &self.is_64bit())
+            .field("capable_133mhz", &self.capable_133mhz())
+            .field("split_completion_discarded", &self.split_completion_discarded())
+            .field("unexpected_split_completion", &self.unexpected_split_completion())
+            .field("complex_device", &self.complex_device())
+            .field("designed_max_memory_read_byte_count", &self.designed_max_memory_read_byte_count())
+            .field(
+                "designed_max_outstanding_split_transactions",
+                &self.designed_max_outstanding_split_transactions(),
+            )
+            .field("received_split_completion_error", &self.received_split_completion_error())
+            .field("capable_266mhz", &self.capable_266mhz())
+            .field("capable_533mhz", &self.capable_533mhz())
+            .finish()
+    }
+}
@@ -0,0 +1,99 @@
+use super::PciCapabilityAddress;
+use crate::ConfigRegionAccess;
+use bit_field::BitField;
+
+/// A power state of a PCI function
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PowerState {
+    D0 = 0b00,
+    D1 = 0b01,
+    D2 = 0b10,
+    D3Hot = 0b11,
+}
+
+impl From<u8> for PowerState {
+    fn from(value: u8) -> Self {
+        match value.get_bits(0..2) {
+            0b00 => PowerState::D0,
+            0b01 => PowerState::D1,
+            0b10 => PowerState::D2,
+            _ => PowerState::D3Hot,
+        }
+    }
+}
+
+bitflags::bitflags! {
+    /// The power states from which a function can generate a PME (Power Management Event).
+    #[repr(transparent)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct PmeSupport: u8 {
+        const D0 = 1 << 0;
+        const D1 = 1 << 1;
+        const D2 = 1 << 2;
+        const D3_HOT = 1 << 3;
+        const D3_COLD = 1 << 4;
+        const _ = !0;
+    }
+}
+
+/// The power states supported by a function, as returned by
+/// [`PowerManagementCapability::supported_states`]. Support for D0 and D3hot is mandatory, so is not
+/// reported.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PowerStateSupport {
+    pub d1: bool,
+    pub d2: bool,
+    /// The states from which the function can generate a PME. If this includes `D3_COLD`, the function
+    /// can signal wakeup events even when main power is removed.
+    pub pme: PmeSupport,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct PowerManagementCapability {
+    pub(super) address: PciCapabilityAddress,
+    capabilities: u16,
+}
+
+impl PowerManagementCapability {
+    pub(crate) fn new(address: PciCapabilityAddress, capabilities: u16) -> PowerManagementCapability {
+        PowerManagementCapability { address, capabilities }
+    }
+
+    /// The version of the PCI Power Management Interface Specification the function complies with
+    pub fn version(&self) -> u8 {
+        self.capabilities.get_bits(0..3) as u8
+    }
+
+    /// The power states supported by the function, and those from which it can generate a PME. This is
+    /// decoded from the Power Management Capabilities register, which is read when the capability is
+    /// parsed.
+    pub fn supported_states(&self) -> PowerStateSupport {
+        PowerStateSupport {
+            d1: self.capabilities.get_bit(9),
+            d2: self.capabilities.get_bit(10),
+            pme: PmeSupport::from_bits_retain(self.capabilities.get_bits(11..16) as u8),
+        }
+    }
+
+    /// The current power state of the function
+    pub fn power_state(&self, access: impl ConfigRegionAccess) -> PowerState {
+        let data = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        PowerState::from(data.get_bits(0..2) as u8)
+    }
+
+    /// Whether the function is in the fully-operational D0 state
+    pub fn is_d0(&self, access: impl ConfigRegionAccess) -> bool {
+        self.power_state(access) == PowerState::D0
+    }
+
+    /// Move the function to a new power state. The caller is responsible for only requesting states that
+    /// the function supports, and for observing the recovery times required by the specification after
+    /// the transition.
+    pub fn set_power_state(&self, state: PowerState, access: impl ConfigRegionAccess) {
+        let mut data = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        data.set_bits(0..2, state as u32);
+        // PME Status is write-1-to-clear, so avoid clearing it unintentionally
+        data.set_bit(15, false);
+        unsafe { access.write(self.address.address, self.address.offset + 0x04, data) };
+    }
+}
@@ -0,0 +1,94 @@
+use super::PciCapabilityAddress;
+use crate::ConfigRegionAccess;
+use bit_field::BitField;
+
+/// The resizing state of a single BAR, as described by the Resizable BAR capability.
+///
+/// Sizes are encoded as powers of two, such that a size of `n` means the BAR is `2^n` MiB.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ResizableBarEntry {
+    /// The slot of the BAR this entry controls
+    pub bar_index: u8,
+    /// The sizes the BAR supports, with bit `n` set if size `n` is supported
+    pub supported_sizes: u64,
+    /// The currently-selected size of the BAR
+    pub current_size: u8,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ResizableBarError {
+    /// The BAR is not controlled by the Resizable BAR capability
+    NoSuchBar,
+    /// The BAR does not support the requested size
+    UnsupportedSize,
+}
+
+/// The Resizable BAR extended capability, which allows software to select a different size for some
+/// of a function's BARs.
+#[derive(Clone, Copy, Debug)]
+pub struct ResizableBarCapability {
+    pub(super) address: PciCapabilityAddress,
+    num_bars: u8,
+}
+
+impl ResizableBarCapability {
+    pub(crate) fn new(address: PciCapabilityAddress, access: impl ConfigRegionAccess) -> ResizableBarCapability {
+        let control = unsafe { access.read(address.address, address.offset + 0x08) };
+        ResizableBarCapability { address, num_bars: control.get_bits(5..8) as u8 }
+    }
+
+    /// The number of BARs that can be resized
+    pub fn num_bars(&self) -> u8 {
+        self.num_bars
+    }
+
+    /// Read the `n`th entry of the capability. Returns `None` if `n` is not less than
+    /// [`ResizableBarCapability::num_bars`].
+    pub fn entry(&self, n: u8, access: impl ConfigRegionAccess) -> Option<ResizableBarEntry> {
+        if n >= self.num_bars {
+            return None;
+        }
+        let offset = self.address.offset + 0x04 + n as u16 * 8;
+        let capability = unsafe { access.read(self.address.address, offset) };
+        let control = unsafe { access.read(self.address.address, offset + 0x04) };
+
+        /*
+         * Sizes of 1MiB to 128TiB are reported in the capability register, and larger sizes in the upper
+         * half of the control register.
+         */
+        let mut supported_sizes = 0u64;
+        supported_sizes.set_bits(0..28, capability.get_bits(4..32) as u64);
+        supported_sizes.set_bits(28..44, control.get_bits(16..32) as u64);
+
+        Some(ResizableBarEntry {
+            bar_index: control.get_bits(0..3) as u8,
+            supported_sizes,
+            current_size: control.get_bits(8..14) as u8,
+        })
+    }
+
+    /// Select the size of the BAR in slot `bar_index`. The BAR should not be decoding (the Memory Space
+    /// Enable bit of the Command register should be clear) while its size is changed, and must be
+    /// reprogrammed afterwards.
+    pub fn set_size(
+        &self,
+        bar_index: u8,
+        size: u8,
+        access: impl ConfigRegionAccess,
+    ) -> Result<(), ResizableBarError> {
+        let (n, entry) = (0..self.num_bars)
+            .find_map(|n| {
+                self.entry(n, &access).filter(|entry| entry.bar_index == bar_index).map(|entry| (n, entry))
+            })
+            .ok_or(ResizableBarError::NoSuchBar)?;
+        if size >= 64 || !entry.supported_sizes.get_bit(size as usize) {
+            return Err(ResizableBarError::UnsupportedSize);
+        }
+
+        let offset = self.address.offset + 0x08 + n as u16 * 8;
+        let mut control = unsafe { access.read(self.address.address, offset) };
+        control.set_bits(8..14, size as u32);
+        unsafe { access.write(self.address.address, offset, control) };
+        Ok(())
+    }
+}
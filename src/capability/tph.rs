@@ -0,0 +1,164 @@
+use super::PciCapabilityAddress;
+use crate::ConfigRegionAccess;
+use bit_field::BitField;
+use core::convert::TryFrom;
+
+/// Where the Steering Tag (ST) table of a TPH Requester is located.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StTableLocation {
+    /// The function does not have an ST table
+    NotPresent = 0b00,
+    /// The ST table is in the TPH Requester capability
+    TphCapability = 0b01,
+    /// The Steering Tags are stored in the upper bits of the Vector Control field of each MSI-X table entry
+    MsixTable = 0b10,
+}
+
+impl TryFrom<u8> for StTableLocation {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0b00 => Ok(StTableLocation::NotPresent),
+            0b01 => Ok(StTableLocation::TphCapability),
+            0b10 => Ok(StTableLocation::MsixTable),
+            _ => Err(()),
+        }
+    }
+}
+
+/// How a TPH Requester chooses the Steering Tags to use for its requests.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StMode {
+    /// All requests use a Steering Tag of `0`
+    NoSt = 0b000,
+    /// The Steering Tag is chosen by the MSI or MSI-X vector associated with the request
+    InterruptVector = 0b001,
+    /// The Steering Tag is chosen in a device-specific way
+    DeviceSpecific = 0b010,
+}
+
+impl TryFrom<u8> for StMode {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0b000 => Ok(StMode::NoSt),
+            0b001 => Ok(StMode::InterruptVector),
+            0b010 => Ok(StMode::DeviceSpecific),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Which kinds of TLP Processing Hints a TPH Requester is permitted to issue.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TphRequesterEnable {
+    Disabled = 0b00,
+    /// Only TPH with 8-bit Steering Tags
+    Tph = 0b01,
+    /// TPH with 8-bit or 16-bit (Extended TPH) Steering Tags
+    TphAndExtendedTph = 0b11,
+}
+
+impl TryFrom<u8> for TphRequesterEnable {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0b00 => Ok(TphRequesterEnable::Disabled),
+            0b01 => Ok(TphRequesterEnable::Tph),
+            0b11 => Ok(TphRequesterEnable::TphAndExtendedTph),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The TLP Processing Hints (TPH) Requester extended capability. TPH allow a function to attach Steering
+/// Tags to its requests, so the platform can steer the data to the cache or memory closest to the CPU
+/// that will consume it.
+#[derive(Clone, Copy, Debug)]
+pub struct TphCapability {
+    pub(super) address: PciCapabilityAddress,
+}
+
+impl TphCapability {
+    pub(crate) fn new(address: PciCapabilityAddress) -> TphCapability {
+        TphCapability { address }
+    }
+
+    /// Whether the function supports [`StMode::NoSt`]. All TPH Requesters must support this mode.
+    pub fn no_st_mode_supported(&self, access: impl ConfigRegionAccess) -> bool {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        reg.get_bit(0)
+    }
+
+    /// Whether the function supports [`StMode::InterruptVector`]
+    pub fn interrupt_vector_mode_supported(&self, access: impl ConfigRegionAccess) -> bool {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        reg.get_bit(1)
+    }
+
+    /// Whether the function supports [`StMode::DeviceSpecific`]
+    pub fn device_specific_mode_supported(&self, access: impl ConfigRegionAccess) -> bool {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        reg.get_bit(2)
+    }
+
+    /// Whether the function supports issuing Extended TPH, with 16-bit Steering Tags
+    pub fn extended_tph_supported(&self, access: impl ConfigRegionAccess) -> bool {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        reg.get_bit(8)
+    }
+
+    /// Where the function's Steering Tag table is located. Returns `None` if the function reports a
+    /// reserved location.
+    pub fn st_table_location(&self, access: impl ConfigRegionAccess) -> Option<StTableLocation> {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        StTableLocation::try_from(reg.get_bits(9..11) as u8).ok()
+    }
+
+    /// The number of entries in the Steering Tag table. Only meaningful if the table is located in the TPH
+    /// Requester capability.
+    pub fn st_table_size(&self, access: impl ConfigRegionAccess) -> u16 {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        reg.get_bits(16..27) as u16 + 1
+    }
+
+    /// The Steering Tag mode the function is using. Returns `None` if a reserved mode is selected.
+    pub fn st_mode(&self, access: impl ConfigRegionAccess) -> Option<StMode> {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x08) };
+        StMode::try_from(reg.get_bits(0..3) as u8).ok()
+    }
+
+    /// Select the Steering Tag mode the function uses. The mode must be supported by the function.
+    pub fn set_st_mode(&self, mode: StMode, access: impl ConfigRegionAccess) {
+        self.update_control(access, |control| {
+            control.set_bits(0..3, mode as u32);
+        });
+    }
+
+    /// Which kinds of TPH the function is permitted to issue. Returns `None` if a reserved value is set.
+    pub fn requester_enable(&self, access: impl ConfigRegionAccess) -> Option<TphRequesterEnable> {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x08) };
+        TphRequesterEnable::try_from(reg.get_bits(8..10) as u8).ok()
+    }
+
+    /// Set which kinds of TPH the function is permitted to issue.
+    pub fn set_requester_enable(&self, enable: TphRequesterEnable, access: impl ConfigRegionAccess) {
+        self.update_control(access, |control| {
+            control.set_bits(8..10, enable as u32);
+        });
+    }
+
+    fn update_control<F>(&self, access: impl ConfigRegionAccess, f: F)
+    where
+        F: FnOnce(&mut u32),
+    {
+        let mut reg = unsafe { access.read(self.address.address, self.address.offset + 0x08) };
+        f(&mut reg);
+        unsafe {
+            access.write(self.address.address, self.address.offset + 0x08, reg);
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use super::PciCapabilityAddress;
+use crate::ConfigRegionAccess;
+use bit_field::BitField;
+
+/// The Vendor-Specific Extended Capability (VSEC), which holds vendor-defined registers identified by a
+/// vendor-defined ID and revision. The vendor is identified by the Vendor ID of the function.
+#[derive(Clone, Copy, Debug)]
+pub struct VendorSpecificExtendedCapability {
+    pub(super) address: PciCapabilityAddress,
+    header: u32,
+}
+
+impl VendorSpecificExtendedCapability {
+    pub(crate) fn new(
+        address: PciCapabilityAddress,
+        access: impl ConfigRegionAccess,
+    ) -> VendorSpecificExtendedCapability {
+        let header = unsafe { access.read(address.address, address.offset + 0x04) };
+        VendorSpecificExtendedCapability { address, header }
+    }
+
+    /// The vendor-defined ID, which identifies the layout of the capability
+    pub fn vsec_id(&self) -> u16 {
+        self.header.get_bits(0..16) as u16
+    }
+
+    /// The vendor-defined revision of the layout of the capability
+    pub fn vsec_revision(&self) -> u8 {
+        self.header.get_bits(16..20) as u8
+    }
+
+    /// The length of the capability in bytes, including the extended capability header and the VSEC
+    /// header
+    pub fn vsec_length(&self) -> u16 {
+        self.header.get_bits(20..32) as u16
+    }
+
+    /// Read the dword at `offset` bytes from the start of the capability. Returns `None` if `offset` is
+    /// not dword-aligned, or if the dword does not lie entirely within the capability.
+    // `u16::is_multiple_of` is only available from Rust 1.87
+    #[allow(clippy::manual_is_multiple_of)]
+    pub fn read(&self, offset: u16, access: impl ConfigRegionAccess) -> Option<u32> {
+        if offset % 4 != 0 || offset as u32 + 4 > self.vsec_length() as u32 {
+            return None;
+        }
+        Some(unsafe { access.read(self.address.address, self.address.offset + offset) })
+    }
+}
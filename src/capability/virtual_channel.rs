@@ -0,0 +1,137 @@
+use super::{PciCapabilityAddress, RegisterNotPresent};
+use crate::ConfigRegionAccess;
+use bit_field::BitField;
+use core::fmt::{self, Debug, Formatter};
+
+/// The Virtual Channel extended capability, which describes and controls the Virtual Channels (VCs) a
+/// port supports for differentiating traffic. Each VC, from VC0 up to the extended VC count, has a VC
+/// Resource with its own capability, control, and status registers.
+#[derive(Clone, Copy, Debug)]
+pub struct VirtualChannelCapability {
+    pub(super) address: PciCapabilityAddress,
+    extended_vc_count: u8,
+}
+
+impl VirtualChannelCapability {
+    pub(crate) fn new(address: PciCapabilityAddress, access: impl ConfigRegionAccess) -> VirtualChannelCapability {
+        let capability = unsafe { access.read(address.address, address.offset + 0x04) };
+        VirtualChannelCapability { address, extended_vc_count: capability.get_bits(0..3) as u8 }
+    }
+
+    /// The number of VCs supported in addition to VC0. There are `extended_vc_count() + 1` VC Resources.
+    pub fn extended_vc_count(&self) -> u8 {
+        self.extended_vc_count
+    }
+
+    /// The number of extended VCs, starting from VC1, that belong to the low-priority VC group, which is
+    /// arbitrated between according to the VC Arbitration Capability
+    pub fn low_priority_extended_vc_count(&self, access: impl ConfigRegionAccess) -> u8 {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        reg.get_bits(4..7) as u8
+    }
+
+    /// The size of each entry of the Port Arbitration Tables, in bits
+    pub fn port_arbitration_table_entry_size(&self, access: impl ConfigRegionAccess) -> u8 {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x04) };
+        1 << reg.get_bits(10..12)
+    }
+
+    /// The VC arbitration schemes supported by the port for the low-priority VC group, as a bitmask
+    pub fn vc_arbitration_capability(&self, access: impl ConfigRegionAccess) -> u8 {
+        let reg = unsafe { access.read(self.address.address, self.address.offset + 0x08) };
+        reg.get_bits(0..8) as u8
+    }
+
+    /// The offset of the VC Resource registers of VC `n`, or `None` if there is no such VC
+    fn resource_offset(&self, n: u8) -> Option<u16> {
+        if n > self.extended_vc_count {
+            return None;
+        }
+        Some(self.address.offset + 0x10 + n as u16 * 0x0c)
+    }
+
+    /// The port arbitration schemes supported by VC `n`, as a bitmask. Returns `None` if there is no
+    /// such VC.
+    pub fn port_arbitration_capability(&self, n: u8, access: impl ConfigRegionAccess) -> Option<u8> {
+        let offset = self.resource_offset(n)?;
+        let reg = unsafe { access.read(self.address.address, offset) };
+        Some(reg.get_bits(0..8) as u8)
+    }
+
+    /// Read the VC Resource Control register of VC `n`. Returns `None` if there is no such VC.
+    pub fn resource_control(&self, n: u8, access: impl ConfigRegionAccess) -> Option<VcResourceControl> {
+        let offset = self.resource_offset(n)?;
+        Some(VcResourceControl(unsafe { access.read(self.address.address, offset + 0x04) }))
+    }
+
+    /// Write the VC Resource Control register of VC `n`.
+    pub fn set_resource_control(
+        &self,
+        n: u8,
+        control: VcResourceControl,
+        access: impl ConfigRegionAccess,
+    ) -> Result<(), RegisterNotPresent> {
+        let offset = self.resource_offset(n).ok_or(RegisterNotPresent)?;
+        unsafe {
+            access.write(self.address.address, offset + 0x04, control.0);
+        }
+        Ok(())
+    }
+
+    /// Whether VC `n` is still being negotiated with the other end of the link after being enabled, in
+    /// which case it cannot be used yet. Returns `None` if there is no such VC.
+    pub fn negotiation_pending(&self, n: u8, access: impl ConfigRegionAccess) -> Option<bool> {
+        let offset = self.resource_offset(n)?;
+        let reg = unsafe { access.read(self.address.address, offset + 0x08) };
+        Some(reg.get_bit(17))
+    }
+}
+
+/// The VC Resource Control register of a Virtual Channel.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct VcResourceControl(u32);
+
+impl VcResourceControl {
+    pub fn new(value: u32) -> Self {
+        VcResourceControl(value)
+    }
+
+    pub fn bits(&self) -> u32 {
+        self.0
+    }
+
+    /// The Traffic Classes mapped to this VC, with bit `n` set if TC`n` is mapped
+    pub fn tc_vc_map(&self) -> u8 {
+        self.0.get_bits(0..8) as u8
+    }
+
+    pub fn set_tc_vc_map(&mut self, map: u8) {
+        self.0.set_bits(0..8, map as u32);
+    }
+
+    pub fn vc_id(&self) -> u8 {
+        self.0.get_bits(24..27) as u8
+    }
+
+    pub fn set_vc_id(&mut self, id: u8) {
+        self.0.set_bits(24..27, id.get_bits(0..3) as u32);
+    }
+
+    pub fn vc_enable(&self) -> bool {
+        self.0.get_bit(31)
+    }
+
+    pub fn set_vc_enable(&mut self, enable: bool) {
+        self.0.set_bit(31, enable);
+    }
+}
+
+impl Debug for VcResourceControl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VcResourceControl")
+            .field("tc_vc_map", &self.tc_vc_map())
+            .field("vc_id", &self.vc_id())
+            .field("vc_enable", &self.vc_enable())
+            .finish()
+    }
+}
@@ -0,0 +1,34 @@
+use crate::{ConfigRegionAccess, PciAddress};
+
+/// A [`ConfigRegionAccess`] that wraps another, and checks that every offset read from or written to is
+/// dword-aligned before passing the access on. Configuration space registers are accessed a dword at a
+/// time, so a misaligned offset is always a bug in the caller, and can fault on some hardware.
+///
+/// The check is a `debug_assert!`, so this wrapper has no cost in release builds.
+#[derive(Clone, Copy, Debug)]
+pub struct CheckedAccess<A: ConfigRegionAccess> {
+    inner: A,
+}
+
+impl<A: ConfigRegionAccess> CheckedAccess<A> {
+    pub fn new(inner: A) -> CheckedAccess<A> {
+        CheckedAccess { inner }
+    }
+
+    /// Unwrap the underlying accessor
+    pub fn into_inner(self) -> A {
+        self.inner
+    }
+}
+
+impl<A: ConfigRegionAccess> ConfigRegionAccess for CheckedAccess<A> {
+    unsafe fn read(&self, address: PciAddress, offset: u16) -> u32 {
+        debug_assert_eq!(offset & 0b11, 0, "misaligned configuration space read at {}+{:#x}", address, offset);
+        self.inner.read(address, offset)
+    }
+
+    unsafe fn write(&self, address: PciAddress, offset: u16, value: u32) {
+        debug_assert_eq!(offset & 0b11, 0, "misaligned configuration space write at {}+{:#x}", address, offset);
+        self.inner.write(address, offset, value)
+    }
+}
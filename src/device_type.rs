@@ -1,5 +1,5 @@
 use crate::{BaseClass, Interface, SubClass};
-use core::convert::TryFrom;
+use core::{convert::TryFrom, fmt};
 
 /// Combines the Base Class and the Sub-class of a device to classify it into a `DeviceType`. Combined with the
 /// device's Interface, this can be enough to know how to drive the device.
@@ -313,6 +313,178 @@ impl From<(BaseClass, SubClass)> for DeviceType {
     }
 }
 
+impl DeviceType {
+    /// The broad category of the device, corresponding to its Base Class.
+    pub fn category(&self) -> DeviceCategory {
+        match self {
+            DeviceType::Unknown => DeviceCategory::Unknown,
+
+            DeviceType::LegacyVgaCompatible | DeviceType::LegacyNotVgaCompatible => DeviceCategory::Legacy,
+
+            DeviceType::ScsiBusController
+            | DeviceType::IdeController
+            | DeviceType::FloppyController
+            | DeviceType::IpiBusController
+            | DeviceType::RaidController
+            | DeviceType::AtaController
+            | DeviceType::SataController
+            | DeviceType::SasController
+            | DeviceType::NvmeController
+            | DeviceType::UfsController
+            | DeviceType::OtherMassStorageController => DeviceCategory::Storage,
+
+            DeviceType::EthernetController
+            | DeviceType::TokenRingController
+            | DeviceType::FddiController
+            | DeviceType::AtmController
+            | DeviceType::IsdnController
+            | DeviceType::WorldFipController
+            | DeviceType::PicmgController
+            | DeviceType::OtherNetworkController => DeviceCategory::Network,
+
+            DeviceType::VgaCompatibleController
+            | DeviceType::XgaController
+            | DeviceType::ThreeDController
+            | DeviceType::OtherDisplayController => DeviceCategory::Display,
+
+            DeviceType::VideoDevice
+            | DeviceType::AudioDevice
+            | DeviceType::TelephonyDevice
+            | DeviceType::OtherMultimediaDevice => DeviceCategory::Multimedia,
+
+            DeviceType::RamController | DeviceType::FlashController | DeviceType::OtherMemoryController => {
+                DeviceCategory::Memory
+            }
+
+            DeviceType::HostBridge
+            | DeviceType::IsaBridge
+            | DeviceType::EisaBridge
+            | DeviceType::McaBridge
+            | DeviceType::PciPciBridge
+            | DeviceType::PcmciaBridge
+            | DeviceType::NuBusBridge
+            | DeviceType::CardBusBridge
+            | DeviceType::RacewayBridge
+            | DeviceType::SemiTransparentPciPciBridge
+            | DeviceType::InfinibandPciHostBridge
+            | DeviceType::OtherBridgeDevice => DeviceCategory::Bridge,
+
+            DeviceType::SerialController
+            | DeviceType::ParallelPort
+            | DeviceType::MultiportSerialController
+            | DeviceType::Modem
+            | DeviceType::GpibController
+            | DeviceType::SmartCard
+            | DeviceType::OtherCommunicationsDevice => DeviceCategory::Communications,
+
+            DeviceType::InterruptController
+            | DeviceType::DmaController
+            | DeviceType::SystemTimer
+            | DeviceType::RtcController
+            | DeviceType::GenericPciHotPlugController
+            | DeviceType::SdHostController
+            | DeviceType::OtherSystemPeripheral => DeviceCategory::SystemPeripheral,
+
+            DeviceType::KeyboardController
+            | DeviceType::Digitizer
+            | DeviceType::MouseController
+            | DeviceType::ScannerController
+            | DeviceType::GameportController
+            | DeviceType::OtherInputController => DeviceCategory::Input,
+
+            DeviceType::GenericDockingStation | DeviceType::OtherDockingStation => DeviceCategory::DockingStation,
+
+            DeviceType::Processor386
+            | DeviceType::Processor486
+            | DeviceType::ProcessorPentium
+            | DeviceType::ProcessorAlpha
+            | DeviceType::ProcessorPowerPc
+            | DeviceType::ProcessorMips
+            | DeviceType::CoProcessor => DeviceCategory::Processor,
+
+            DeviceType::FirewireController
+            | DeviceType::AccessBusController
+            | DeviceType::SsaBusController
+            | DeviceType::UsbController
+            | DeviceType::FibreChannelController
+            | DeviceType::SmBusController
+            | DeviceType::InfiniBandController
+            | DeviceType::IpmiController
+            | DeviceType::SercosController
+            | DeviceType::CanBusController => DeviceCategory::SerialBus,
+
+            DeviceType::IrdaController
+            | DeviceType::ConsumerIrController
+            | DeviceType::RfController
+            | DeviceType::BluetoothController
+            | DeviceType::BroadbandController
+            | DeviceType::Ethernet5GHzController
+            | DeviceType::Ethernet24GHzController
+            | DeviceType::OtherWirelessController => DeviceCategory::Wireless,
+
+            DeviceType::IntelligentIoController => DeviceCategory::IntelligentIo,
+
+            DeviceType::TvSatelliteCommunicationsController
+            | DeviceType::AudioSatelliteCommunicationsController
+            | DeviceType::VoiceSatelliteCommunicationsController
+            | DeviceType::DataSatelliteCommunicationsController => DeviceCategory::SatelliteCommunications,
+
+            DeviceType::NetworkCryptionController
+            | DeviceType::EntertainmentCryptionController
+            | DeviceType::OtherCryptionController => DeviceCategory::Encryption,
+
+            DeviceType::DpioModule
+            | DeviceType::PerformanceCounter
+            | DeviceType::CommunicationsSynchronizationController
+            | DeviceType::ManagementCard
+            | DeviceType::OtherSignalProcessingController => DeviceCategory::SignalProcessing,
+        }
+    }
+}
+
+/// A broad category of device, as returned by [`DeviceType::category`]. Each category corresponds to one
+/// Base Class.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DeviceCategory {
+    Unknown,
+    /// Base Class 0x00 - Devices that predate Class Codes
+    Legacy,
+    /// Base Class 0x01 - Mass Storage Controllers
+    Storage,
+    /// Base Class 0x02 - Network Controllers
+    Network,
+    /// Base Class 0x03 - Display Controllers
+    Display,
+    /// Base Class 0x04 - Multimedia Devices
+    Multimedia,
+    /// Base Class 0x05 - Memory Controllers
+    Memory,
+    /// Base Class 0x06 - Bridge Devices
+    Bridge,
+    /// Base Class 0x07 - Simple Communications Controllers
+    Communications,
+    /// Base Class 0x08 - Generic System Peripherals
+    SystemPeripheral,
+    /// Base Class 0x09 - Input Devices
+    Input,
+    /// Base Class 0x0a - Docking Stations
+    DockingStation,
+    /// Base Class 0x0b - Processors
+    Processor,
+    /// Base Class 0x0c - Serial Bus Controllers
+    SerialBus,
+    /// Base Class 0x0d - Wireless Controllers
+    Wireless,
+    /// Base Class 0x0e - Intelligent IO Controllers
+    IntelligentIo,
+    /// Base Class 0x0f - Satellite Communications Controllers
+    SatelliteCommunications,
+    /// Base Class 0x10 - Encryption and Decryption Controllers
+    Encryption,
+    /// Base Class 0x11 - Data Acquisition and Signal Processing Controllers
+    SignalProcessing,
+}
+
 /// The different register-level programming interfaces defined for USB controllers (devices of type
 /// `DeviceType::UsbController`).
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -340,3 +512,47 @@ impl TryFrom<Interface> for UsbType {
         }
     }
 }
+
+/// The programming interface of a device, decoded into an enum for device types where the crate knows
+/// the meaning of the Interface byte.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DeviceInterface {
+    Usb(UsbType),
+    /// The raw Interface byte, for device types (or interfaces) the crate does not decode.
+    Other(Interface),
+}
+
+/// The class of a device, combining its `DeviceType` with its decoded programming interface.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DeviceClassification {
+    pub device_type: DeviceType,
+    pub interface: DeviceInterface,
+}
+
+impl From<(BaseClass, SubClass, Interface)> for DeviceClassification {
+    fn from((base_class, sub_class, interface): (BaseClass, SubClass, Interface)) -> Self {
+        let device_type = DeviceType::from((base_class, sub_class));
+        let interface = match device_type {
+            DeviceType::UsbController => {
+                UsbType::try_from(interface).map(DeviceInterface::Usb).unwrap_or(DeviceInterface::Other(interface))
+            }
+            _ => DeviceInterface::Other(interface),
+        };
+        DeviceClassification { device_type, interface }
+    }
+}
+
+/// The Base Class, Sub-class, and Interface of a function. Formats as the colon-separated hex triple used
+/// by tools such as `lspci`, e.g. `0c:03:30`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ClassCode {
+    pub base_class: BaseClass,
+    pub sub_class: SubClass,
+    pub interface: Interface,
+}
+
+impl fmt::Display for ClassCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02x}:{:02x}:{:02x}", self.base_class, self.sub_class, self.interface)
+    }
+}
@@ -0,0 +1,94 @@
+use crate::{ConfigRegionAccess, PciAddress, PciHeader};
+
+/// A range of buses within a PCI segment, such as is described by an entry of the ACPI MCFG table.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SegmentRange {
+    pub segment: u16,
+    pub bus_start: u8,
+    /// The last bus of the range (inclusive)
+    pub bus_end: u8,
+}
+
+/// A function found by [`PciEnumerator`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct EnumeratedFunction {
+    pub address: PciAddress,
+    /// Whether the device the function is part of has multiple functions, as reported by its function 0
+    pub multifunction: bool,
+}
+
+/// Enumerates the present functions on a set of buses by brute-force, producing them in address order.
+///
+/// Every device on every bus is probed, and functions 1 to 7 of a device are only probed if function 0
+/// reports that it has multiple functions.
+pub struct PciEnumerator<'a, T: ConfigRegionAccess> {
+    access: T,
+    segments: &'a [SegmentRange],
+    segment: usize,
+    bus: u16,
+    device: u8,
+    function: u8,
+}
+
+impl<'a, T: ConfigRegionAccess> PciEnumerator<'a, T> {
+    /// Enumerate all functions across the given segments and bus ranges.
+    pub fn with_segments(access: T, segments: &'a [SegmentRange]) -> PciEnumerator<'a, T> {
+        let bus = segments.first().map(|range| range.bus_start as u16).unwrap_or(0);
+        PciEnumerator { access, segments, segment: 0, bus, device: 0, function: 0 }
+    }
+}
+
+impl<T: ConfigRegionAccess> Iterator for PciEnumerator<'_, T> {
+    type Item = EnumeratedFunction;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let range = self.segments.get(self.segment)?;
+
+            if self.bus > range.bus_end as u16 {
+                self.segment += 1;
+                self.bus = self.segments.get(self.segment).map(|range| range.bus_start as u16).unwrap_or(0);
+                self.device = 0;
+                self.function = 0;
+                continue;
+            }
+            if self.device >= 32 {
+                self.bus += 1;
+                self.device = 0;
+                self.function = 0;
+                continue;
+            }
+            if self.function >= 8 {
+                self.device += 1;
+                self.function = 0;
+                continue;
+            }
+
+            let address = PciAddress::new(range.segment, self.bus as u8, self.device, self.function);
+            let header = PciHeader::new(address);
+
+            if self.function == 0 {
+                if !header.device_exists(&self.access) {
+                    self.device += 1;
+                    continue;
+                }
+
+                /*
+                 * Only probe the other functions of multi-function devices, as some single-function
+                 * devices respond to accesses of every function number.
+                 */
+                let multifunction = header.has_multiple_functions(&self.access);
+                self.function = if multifunction { 1 } else { 8 };
+                return Some(EnumeratedFunction { address, multifunction });
+            }
+
+            /*
+             * Functions other than function 0 are only probed on multi-function devices.
+             */
+            self.function += 1;
+            if header.device_exists(&self.access) {
+                return Some(EnumeratedFunction { address, multifunction: true });
+            }
+        }
+    }
+}
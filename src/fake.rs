@@ -0,0 +1,64 @@
+use crate::{ConfigRegionAccess, PciAddress};
+use alloc::collections::BTreeMap;
+use core::cell::RefCell;
+
+#[derive(Clone, Copy, Debug)]
+struct Register {
+    value: u32,
+    writable: u32,
+}
+
+/// An in-memory configuration space, useful for testing code that uses this crate without access to
+/// real hardware.
+///
+/// Registers that have not been set read as `0xffffffff`, which is what hardware returns for functions
+/// that are not present. By default, every bit of a register is writable, but bits can be made read-only
+/// with [`FakeConfigSpace::set_writable_bits`] to model registers such as BARs, which only allow the
+/// upper bits to be written so that their size can be probed.
+#[derive(Debug, Default)]
+pub struct FakeConfigSpace {
+    registers: RefCell<BTreeMap<(PciAddress, u16), Register>>,
+}
+
+impl FakeConfigSpace {
+    pub fn new() -> FakeConfigSpace {
+        FakeConfigSpace { registers: RefCell::new(BTreeMap::new()) }
+    }
+
+    /// Set the dword at `offset` in the configuration space of `address`, regardless of which of its bits
+    /// are writable.
+    pub fn set(&self, address: PciAddress, offset: u16, value: u32) {
+        self.registers
+            .borrow_mut()
+            .entry((address, offset))
+            .and_modify(|register| register.value = value)
+            .or_insert(Register { value, writable: !0 });
+    }
+
+    /// Get the dword at `offset` in the configuration space of `address`.
+    pub fn get(&self, address: PciAddress, offset: u16) -> u32 {
+        self.registers.borrow().get(&(address, offset)).map(|register| register.value).unwrap_or(0xffffffff)
+    }
+
+    /// Set which bits of the dword at `offset` can be changed through [`ConfigRegionAccess::write`]. Bits
+    /// that are not writable retain their value on write.
+    pub fn set_writable_bits(&self, address: PciAddress, offset: u16, writable: u32) {
+        self.registers
+            .borrow_mut()
+            .entry((address, offset))
+            .and_modify(|register| register.writable = writable)
+            .or_insert(Register { value: 0xffffffff, writable });
+    }
+}
+
+impl ConfigRegionAccess for FakeConfigSpace {
+    unsafe fn read(&self, address: PciAddress, offset: u16) -> u32 {
+        self.get(address, offset)
+    }
+
+    unsafe fn write(&self, address: PciAddress, offset: u16, value: u32) {
+        let mut registers = self.registers.borrow_mut();
+        let register = registers.entry((address, offset)).or_insert(Register { value: 0xffffffff, writable: !0 });
+        register.value = (register.value & !register.writable) | (value & register.writable);
+    }
+}
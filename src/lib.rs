@@ -1,14 +1,35 @@
 #![no_std]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 pub mod capability;
+mod checked;
 pub mod device_type;
+mod enumerator;
+#[cfg(feature = "alloc")]
+mod fake;
 mod register;
-
-pub use register::{CommandRegister, DevselTiming, StatusRegister};
-
-use crate::capability::CapabilityIterator;
+pub mod rom;
+#[cfg(feature = "trace")]
+mod trace;
+
+pub use checked::CheckedAccess;
+pub use enumerator::{EnumeratedFunction, PciEnumerator, SegmentRange};
+#[cfg(feature = "alloc")]
+pub use fake::FakeConfigSpace;
+pub use register::{BridgeControl, CommandRegister, DevselTiming, PcieStatusView, StatusRegister};
+#[cfg(feature = "trace")]
+pub use trace::{TraceEvent, TracingAccess};
+
+#[cfg(feature = "alloc")]
+use crate::capability::PciCapability;
+use crate::{
+    capability::{CapabilityIterator, ExtendedCapabilityIterator, RawCapabilityIterator},
+    device_type::{ClassCode, DeviceClassification, DeviceType},
+};
 use bit_field::BitField;
-use core::fmt;
+use core::{convert::TryFrom, fmt, ops::RangeInclusive};
 
 /// The address of a PCIe function.
 ///
@@ -20,6 +41,10 @@ use core::fmt;
 ///  |            segment            |      bus      | device  | func |
 ///  +-------------------------------+---------------+---------+------+
 /// ```
+///
+/// Addresses are ordered by segment, then bus, then device, then function, so sorting a list of
+/// addresses produces them in topological order. This ordering is guaranteed, and will be preserved even
+/// if the internal representation changes.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
 pub struct PciAddress(u32);
 
@@ -48,8 +73,89 @@ impl PciAddress {
     pub fn function(&self) -> u8 {
         self.0.get_bits(0..3) as u8
     }
+
+    pub fn components(&self) -> PciAddressComponents {
+        PciAddressComponents {
+            segment: self.segment(),
+            bus: self.bus(),
+            device: self.device(),
+            function: self.function(),
+        }
+    }
+
+    /// Check whether `other` is a function of the same device as this address (i.e. they only differ
+    /// in function number).
+    pub fn same_device(&self, other: &PciAddress) -> bool {
+        self.0.get_bits(3..32) == other.0.get_bits(3..32)
+    }
+
+    /// Check whether `other` is on the same bus, in the same segment, as this address.
+    pub fn same_bus(&self, other: &PciAddress) -> bool {
+        self.0.get_bits(8..32) == other.0.get_bits(8..32)
+    }
+
+    /// Iterate over every address from `start` (inclusive) to `end` (exclusive), in the same order as
+    /// addresses are sorted. The function number is incremented first, carrying into the device number,
+    /// then the bus number, then the segment. For example, all functions on a bus can be visited with
+    /// `PciAddress::range(PciAddress::new(segment, bus, 0, 0), PciAddress::new(segment, bus + 1, 0, 0))`.
+    ///
+    /// This just produces addresses - it does not check whether functions are present at them.
+    pub fn range(start: PciAddress, end: PciAddress) -> PciAddressRange {
+        PciAddressRange { next: start.0, end: end.0 }
+    }
+}
+
+/// An iterator over a range of [`PciAddress`]es, created by [`PciAddress::range`].
+#[derive(Clone, Debug)]
+pub struct PciAddressRange {
+    next: u32,
+    end: u32,
 }
 
+impl Iterator for PciAddressRange {
+    type Item = PciAddress;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.end {
+            return None;
+        }
+        let address = PciAddress(self.next);
+        self.next += 1;
+        Some(address)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end.saturating_sub(self.next) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for PciAddressRange {}
+
+/// The components of a [`PciAddress`] as named fields, for interoperating with code (such as ACPI
+/// tables) that describes functions in this form.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PciAddressComponents {
+    pub segment: u16,
+    pub bus: u8,
+    pub device: u8,
+    pub function: u8,
+}
+
+impl From<PciAddress> for PciAddressComponents {
+    fn from(address: PciAddress) -> Self {
+        address.components()
+    }
+}
+
+impl From<PciAddressComponents> for PciAddress {
+    fn from(components: PciAddressComponents) -> Self {
+        PciAddress::new(components.segment, components.bus, components.device, components.function)
+    }
+}
+
+/// Formats the address in the `segment:bus:device.function` form used by `lspci -D`, e.g. `0000:03:00.0`. The
+/// segment is always printed as four hex digits, the bus and device as two, and the function as one.
 impl fmt::Display for PciAddress {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{:04x}:{:02x}:{:02x}.{}", self.segment(), self.bus(), self.device(), self.function())
@@ -73,6 +179,56 @@ pub type SubsystemVendorId = u16;
 pub type InterruptLine = u8;
 pub type InterruptPin = u8;
 
+/// The legacy INTx# interrupt pin used by a function, decoded from the raw [`InterruptPin`] value. A raw
+/// value of `0` means the function does not use a legacy interrupt pin, so it has no corresponding
+/// variant, and is represented as `None` by [`EndpointHeader::interrupt_pin`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LegacyInterruptPin {
+    IntA,
+    IntB,
+    IntC,
+    IntD,
+}
+
+impl TryFrom<InterruptPin> for LegacyInterruptPin {
+    type Error = ();
+
+    fn try_from(value: InterruptPin) -> Result<Self, Self::Error> {
+        match value {
+            0x01 => Ok(LegacyInterruptPin::IntA),
+            0x02 => Ok(LegacyInterruptPin::IntB),
+            0x03 => Ok(LegacyInterruptPin::IntC),
+            0x04 => Ok(LegacyInterruptPin::IntD),
+            _ => Err(()),
+        }
+    }
+}
+
+impl From<LegacyInterruptPin> for InterruptPin {
+    fn from(pin: LegacyInterruptPin) -> Self {
+        match pin {
+            LegacyInterruptPin::IntA => 0x01,
+            LegacyInterruptPin::IntB => 0x02,
+            LegacyInterruptPin::IntC => 0x03,
+            LegacyInterruptPin::IntD => 0x04,
+        }
+    }
+}
+
+/// Find the interrupt pin on the upstream side of a PCI-PCI bridge that a legacy interrupt is routed to,
+/// given the pin used by a function with device number `device` on the bridge's secondary bus. This is the
+/// standard swizzle described by the PCI-to-PCI Bridge Architecture Specification, and can be applied
+/// repeatedly to route an interrupt through several bridges.
+///
+/// Only pins `1..=4` (INTA# to INTD#) are swizzled. Any other `pin`, including `0` (no interrupt pin) and
+/// the all-ones value read from an absent function, produces `0`.
+pub fn swizzle_interrupt_pin(pin: InterruptPin, device: u8) -> InterruptPin {
+    if !(1..=4).contains(&pin) {
+        return 0;
+    }
+    ((pin as u16 - 1 + device as u16) % 4 + 1) as InterruptPin
+}
+
 // TODO: documentation
 pub trait ConfigRegionAccess {
     /// Performs a PCI read at `address` with `offset`.
@@ -111,6 +267,18 @@ pub enum HeaderType {
     Unknown(u8),
 }
 
+impl HeaderType {
+    /// The raw value of the Header Type field (excluding the multi-function bit)
+    pub fn as_u8(&self) -> u8 {
+        match *self {
+            HeaderType::Endpoint => 0x00,
+            HeaderType::PciPciBridge => 0x01,
+            HeaderType::CardBusBridge => 0x02,
+            HeaderType::Unknown(t) => t,
+        }
+    }
+}
+
 /// Every PCI configuration region starts with a header made up of two parts:
 ///    - a predefined region that identify the function (bytes `0x00..0x10`)
 ///    - a device-dependent region that depends on the Header Type field
@@ -148,6 +316,25 @@ impl PciHeader {
         (id.get_bits(0..16) as VendorId, id.get_bits(16..32) as DeviceId)
     }
 
+    /// The Vendor ID and Device ID packed into a single value, exactly as they are laid out in
+    /// configuration space: the Device ID in bits `16..32`, and the Vendor ID in bits `0..16`.
+    pub fn id_u32(&self, access: impl ConfigRegionAccess) -> u32 {
+        unsafe { access.read(self.0, 0x00) }
+    }
+
+    /// A compact identifier for the function, made up of its address and its Vendor and Device IDs, for
+    /// use in logs. Formats as e.g. `0000:03:00.0 [8086:1521]`.
+    pub fn ident(&self, access: impl ConfigRegionAccess) -> DeviceIdent {
+        let (vendor_id, device_id) = self.id(access);
+        DeviceIdent { address: self.0, vendor_id, device_id }
+    }
+
+    /// Check whether a function is present at this address. Reads of functions that are not present
+    /// return all-ones, which is not a valid Vendor ID.
+    pub(crate) fn device_exists(&self, access: impl ConfigRegionAccess) -> bool {
+        self.id(access).0 != 0xffff
+    }
+
     pub fn header_type(&self, access: impl ConfigRegionAccess) -> HeaderType {
         /*
          * Read bits 0..=6 of the Header Type. Bit 7 dictates whether the device has multiple functions and so
@@ -161,6 +348,17 @@ impl PciHeader {
         }
     }
 
+    /// Convert this header into the concrete header type of the function, reading the Header Type only
+    /// once.
+    pub fn specialize(self, access: impl ConfigRegionAccess) -> SpecializedHeader {
+        match self.header_type(access) {
+            HeaderType::Endpoint => SpecializedHeader::Endpoint(EndpointHeader(self.0)),
+            HeaderType::PciPciBridge => SpecializedHeader::PciPciBridge(PciPciBridgeHeader(self.0)),
+            HeaderType::CardBusBridge => SpecializedHeader::CardBusBridge(self),
+            HeaderType::Unknown(header_type) => SpecializedHeader::Unknown(self, header_type),
+        }
+    }
+
     pub fn has_multiple_functions(&self, access: impl ConfigRegionAccess) -> bool {
         /*
          * Reads bit 7 of the Header Type, which is 1 if the device has multiple functions.
@@ -168,6 +366,59 @@ impl PciHeader {
         unsafe { access.read(self.0, 0x0c) }.get_bit(23)
     }
 
+    /// The system cache line size, in units of dwords
+    pub fn cache_line_size(&self, access: impl ConfigRegionAccess) -> u8 {
+        unsafe { access.read(self.0, 0x0c) }.get_bits(0..8) as u8
+    }
+
+    pub fn set_cache_line_size(&mut self, size: u8, access: impl ConfigRegionAccess) {
+        self.update_header_dword(access, |data| {
+            data.set_bits(0..8, size as u32);
+        });
+    }
+
+    /// The Latency Timer, in units of PCI bus clocks. For PCI-PCI bridges, this is the Primary Latency
+    /// Timer. Hardwired to `0` on PCI Express.
+    pub fn latency_timer(&self, access: impl ConfigRegionAccess) -> u8 {
+        unsafe { access.read(self.0, 0x0c) }.get_bits(8..16) as u8
+    }
+
+    pub fn set_latency_timer(&mut self, timer: u8, access: impl ConfigRegionAccess) {
+        self.update_header_dword(access, |data| {
+            data.set_bits(8..16, timer as u32);
+        });
+    }
+
+    /// Read-modify-write the dword at `0x0c`. Writing `1` to the Start BIST bit starts the function's
+    /// self-test, so it is always written as `0`.
+    fn update_header_dword(&mut self, access: impl ConfigRegionAccess, f: impl FnOnce(&mut u32)) {
+        let mut data = unsafe { access.read(self.0, 0x0c) };
+        f(&mut data);
+        data.set_bit(30, false);
+        unsafe {
+            access.write(self.0, 0x0c, data);
+        }
+    }
+
+    /// Returns `true` if functions 1 to 7 of this device should be probed during enumeration - that is,
+    /// if function 0 of the device is present and reports that the device has multiple functions.
+    /// Probing the other functions of single-function devices can confuse some hardware.
+    pub fn probe_subfunctions(&self, access: impl ConfigRegionAccess) -> bool {
+        let function_0 = PciHeader::new(PciAddress::new(self.0.segment(), self.0.bus(), self.0.device(), 0));
+        function_0.device_exists(&access) && function_0.has_multiple_functions(&access)
+    }
+
+    /// Iterate over the present functions of the device at this address. Function 0 is always
+    /// produced, and functions 1 to 7 are only produced if function 0 reports that the device has
+    /// multiple functions, and only if they are present.
+    pub fn functions<T: ConfigRegionAccess>(&self, access: T) -> FunctionIterator<T> {
+        FunctionIterator { address: self.0, function: 0, multifunction: false, access }
+    }
+
+    pub fn revision(&self, access: impl ConfigRegionAccess) -> DeviceRevision {
+        unsafe { access.read(self.0, 0x08) }.get_bits(0..8) as DeviceRevision
+    }
+
     pub fn revision_and_class(
         &self,
         access: impl ConfigRegionAccess,
@@ -181,11 +432,109 @@ impl PciHeader {
         )
     }
 
+    /// The Class Code as a single 24-bit value of the form `(base << 16) | (sub << 8) | interface`, as
+    /// commonly used by PCI ID databases and tools such as `lspci`.
+    pub fn class_code(&self, access: impl ConfigRegionAccess) -> u32 {
+        unsafe { access.read(self.0, 0x08) }.get_bits(8..32)
+    }
+
+    /// Classify the function by its class code, decoding the Base Class and Sub-class into a `DeviceType`,
+    /// and the Interface into an enum where its meaning is known.
+    pub fn classify(&self, access: impl ConfigRegionAccess) -> DeviceClassification {
+        let (_, base_class, sub_class, interface) = self.revision_and_class(access);
+        DeviceClassification::from((base_class, sub_class, interface))
+    }
+
+    /// Read the class code of the function, both decoded into a `DeviceType`, and as a [`ClassCode`] that
+    /// can be formatted in the form used by tools such as `lspci`.
+    pub fn class_string(&self, access: impl ConfigRegionAccess) -> (DeviceType, ClassCode) {
+        let (_, base_class, sub_class, interface) = self.revision_and_class(access);
+        (DeviceType::from((base_class, sub_class)), ClassCode { base_class, sub_class, interface })
+    }
+
     pub fn status(&self, access: impl ConfigRegionAccess) -> StatusRegister {
         let data = unsafe { access.read(self.0, 0x4).get_bits(16..32) };
         StatusRegister::new(data as u16)
     }
 
+    /// Clear the bits of the Status register that are set in `mask`. The error bits of the Status
+    /// register are write-1-to-clear, so this writes `mask` to the register while preserving the Command
+    /// register, which shares its dword.
+    pub fn clear_status(&mut self, access: impl ConfigRegionAccess, mask: StatusRegister) {
+        let mut data = unsafe { access.read(self.0, 0x4) };
+        data.set_bits(16..32, mask.bits() as u32);
+        unsafe {
+            access.write(self.0, 0x4, data);
+        }
+    }
+
+    /// Read the offset of the first capability in the capability list. Returns `None` if the function does
+    /// not have a capability list, or if the pointer is invalid (capabilities can't live in the header, so
+    /// must be at offset `0x40` or above). The pointer is at the same offset in Type-0 and Type-1 headers,
+    /// but not in CardBus bridge headers.
+    ///
+    /// Also returns `None` if the Status register or pointer read as all-ones, as they do when the function
+    /// is absent or has been removed.
+    pub fn capability_pointer(&self, access: impl ConfigRegionAccess) -> Option<u8> {
+        let status = self.status(&access);
+        if status.bits() == 0xffff || !status.has_capability_list() {
+            return None;
+        }
+
+        let raw = unsafe { access.read(self.0, 0x34).get_bits(0..8) as u8 };
+        if raw == 0xff {
+            return None;
+        }
+
+        /*
+         * The bottom two bits of the pointer are reserved, and must be masked off.
+         */
+        let pointer = raw & !0b11;
+        if pointer < 0x40 {
+            return None;
+        }
+        Some(pointer)
+    }
+
+    /// Iterate over the capabilities of a function with a Type-0 or Type-1 header.
+    pub fn capabilities<T: ConfigRegionAccess>(&self, access: T) -> CapabilityIterator<T> {
+        let pointer = self.capability_pointer(&access).map_or(0, u16::from);
+        CapabilityIterator::new(self.0, pointer, access)
+    }
+
+    /// Iterate over the IDs and addresses of the capabilities of a function with a Type-0 or Type-1 header,
+    /// without decoding them.
+    pub fn raw_capabilities<T: ConfigRegionAccess>(&self, access: T) -> RawCapabilityIterator<T> {
+        let pointer = self.capability_pointer(&access).map_or(0, u16::from);
+        RawCapabilityIterator::new(self.0, pointer, access)
+    }
+
+    /// Read the Subsystem ID and Subsystem Vendor ID of the function. For endpoints these are read from
+    /// the header, and for PCI-PCI bridges from the Bridge Subsystem Vendor ID capability. Returns `None`
+    /// for bridges without that capability, and for other header types.
+    pub fn subsystem(&self, access: impl ConfigRegionAccess) -> Option<(SubsystemId, SubsystemVendorId)> {
+        match self.header_type(&access) {
+            HeaderType::Endpoint => Some(EndpointHeader(self.0).subsystem(access)),
+            HeaderType::PciPciBridge => PciPciBridgeHeader(self.0).subsystem(access),
+            _ => None,
+        }
+    }
+
+    /// Iterate over every dword of the function's configuration space, including extended configuration
+    /// space, producing each dword along with its offset (from `0x000` to `0xffc`). This is useful for
+    /// dumping the configuration space of a function. The `ConfigRegionAccess` must support extended
+    /// configuration space (e.g. ECAM).
+    pub fn extended_config_dwords<T: ConfigRegionAccess>(&self, access: T) -> ConfigDwordIterator<T> {
+        ConfigDwordIterator { address: self.0, offset: 0, access }
+    }
+
+    /// Iterate over the PCI Express extended capabilities of the function. These are only present on
+    /// PCI Express functions, and can only be accessed through a `ConfigRegionAccess` that supports
+    /// extended configuration space (e.g. ECAM).
+    pub fn extended_capabilities<T: ConfigRegionAccess>(&self, access: T) -> ExtendedCapabilityIterator<T> {
+        ExtendedCapabilityIterator::new(self.0, access)
+    }
+
     pub fn command(&self, access: impl ConfigRegionAccess) -> CommandRegister {
         let data = unsafe { access.read(self.0, 0x4).get_bits(0..16) };
         CommandRegister::from_bits_retain(data as u16)
@@ -204,6 +553,87 @@ impl PciHeader {
     }
 }
 
+/// An iterator over the functions of a single device, as returned by [`PciHeader::functions`].
+pub struct FunctionIterator<T: ConfigRegionAccess> {
+    address: PciAddress,
+    function: u8,
+    multifunction: bool,
+    access: T,
+}
+
+impl<T: ConfigRegionAccess> Iterator for FunctionIterator<T> {
+    type Item = PciAddress;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.function < 8 {
+            let address =
+                PciAddress::new(self.address.segment(), self.address.bus(), self.address.device(), self.function);
+            self.function += 1;
+
+            if address.function() == 0 {
+                self.multifunction = PciHeader::new(address).has_multiple_functions(&self.access);
+                return Some(address);
+            }
+
+            if !self.multifunction {
+                self.function = 8;
+                break;
+            }
+
+            if PciHeader::new(address).device_exists(&self.access) {
+                return Some(address);
+            }
+        }
+        None
+    }
+}
+
+/// Iterates over the dwords of a function's configuration space. See [`PciHeader::extended_config_dwords`].
+pub struct ConfigDwordIterator<T: ConfigRegionAccess> {
+    address: PciAddress,
+    offset: u16,
+    access: T,
+}
+
+impl<T: ConfigRegionAccess> Iterator for ConfigDwordIterator<T> {
+    type Item = (u16, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset >= 0x1000 {
+            return None;
+        }
+        let offset = self.offset;
+        self.offset += 4;
+        Some((offset, unsafe { self.access.read(self.address, offset) }))
+    }
+}
+
+/// The address and IDs of a function, as returned by [`PciHeader::ident`]. Formats as the address, followed
+/// by the Vendor and Device IDs in the form used by `lspci -nn`, e.g. `0000:03:00.0 [8086:1521]`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DeviceIdent {
+    pub address: PciAddress,
+    pub vendor_id: VendorId,
+    pub device_id: DeviceId,
+}
+
+impl fmt::Display for DeviceIdent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} [{:04x}:{:04x}]", self.address, self.vendor_id, self.device_id)
+    }
+}
+
+/// A header converted into the concrete type for its Header Type, as returned by
+/// [`PciHeader::specialize`].
+pub enum SpecializedHeader {
+    Endpoint(EndpointHeader),
+    PciPciBridge(PciPciBridgeHeader),
+    /// CardBus bridges are not modelled by a concrete header type, so only the common header is provided.
+    CardBusBridge(PciHeader),
+    /// A header with an unknown Header Type, which is also provided.
+    Unknown(PciHeader, u8),
+}
+
 /// Endpoints have a Type-0 header, so the remainder of the header is of the form:
 /// ```ignore
 ///     32                           16                              0
@@ -253,6 +683,9 @@ impl PciHeader {
 pub struct EndpointHeader(PciAddress);
 
 impl EndpointHeader {
+    /// The number of BAR slots in a Type 0 header
+    pub const MAX_BARS: usize = MAX_BARS;
+
     pub fn from_header(header: PciHeader, access: impl ConfigRegionAccess) -> Option<EndpointHeader> {
         match header.header_type(access) {
             HeaderType::Endpoint => Some(EndpointHeader(header.0)),
@@ -260,6 +693,17 @@ impl EndpointHeader {
         }
     }
 
+    /// Create an `EndpointHeader` without checking the Header Type of the function, for callers that
+    /// have already read it.
+    ///
+    /// # Safety
+    ///
+    /// The function must have a Type-0 header. Otherwise, the methods of `EndpointHeader` will read and
+    /// write registers with a different meaning.
+    pub unsafe fn from_header_unchecked(header: PciHeader) -> EndpointHeader {
+        EndpointHeader(header.0)
+    }
+
     pub fn header(&self) -> PciHeader {
         PciHeader(self.0)
     }
@@ -268,6 +712,10 @@ impl EndpointHeader {
         self.header().status(access)
     }
 
+    pub fn clear_status(&mut self, access: impl ConfigRegionAccess, mask: StatusRegister) {
+        self.header().clear_status(access, mask);
+    }
+
     pub fn command(&self, access: impl ConfigRegionAccess) -> CommandRegister {
         self.header().command(access)
     }
@@ -279,18 +727,58 @@ impl EndpointHeader {
         self.header().update_command(access, f);
     }
 
-    pub fn capability_pointer(&self, access: impl ConfigRegionAccess) -> u16 {
-        let status = self.status(&access);
-        if status.has_capability_list() {
-            unsafe { access.read(self.0, 0x34).get_bits(0..8) as u16 }
-        } else {
-            0
-        }
+    pub fn capability_pointer(&self, access: impl ConfigRegionAccess) -> Option<u8> {
+        self.header().capability_pointer(access)
     }
 
     pub fn capabilities<T: ConfigRegionAccess>(&self, access: T) -> CapabilityIterator<T> {
-        let pointer = self.capability_pointer(&access);
-        CapabilityIterator::new(self.0, pointer, access)
+        self.header().capabilities(access)
+    }
+
+    pub fn raw_capabilities<T: ConfigRegionAccess>(&self, access: T) -> RawCapabilityIterator<T> {
+        self.header().raw_capabilities(access)
+    }
+
+    pub fn extended_capabilities<T: ConfigRegionAccess>(&self, access: T) -> ExtendedCapabilityIterator<T> {
+        self.header().extended_capabilities(access)
+    }
+
+    /// Walk the capability list once, collecting the capabilities so they can be queried repeatedly
+    /// without further configuration space reads.
+    #[cfg(feature = "alloc")]
+    pub fn collect_capabilities(&self, access: impl ConfigRegionAccess) -> alloc::vec::Vec<PciCapability> {
+        self.capabilities(access).collect()
+    }
+
+    /// Check whether the function has a capability with the given ID. This stops walking the capability
+    /// list as soon as a matching capability is found.
+    pub fn has_capability(&self, id: u8, access: impl ConfigRegionAccess) -> bool {
+        self.raw_capabilities(access).any(|(cap_id, _)| u8::from(cap_id) == id)
+    }
+
+    /// Walk the capability list once, returning a set of the IDs of the capabilities present, where bit `N`
+    /// is set if a capability with ID `N` is present. IDs above 31 are not reserved for any capability,
+    /// so are ignored, as are null capabilities.
+    pub fn capability_id_set(&self, access: impl ConfigRegionAccess) -> u32 {
+        self.raw_capabilities(access)
+            .map(|(id, _)| u8::from(id))
+            .filter(|&id| id != 0x00 && id < 32)
+            .fold(0, |set, id| set | (1 << id))
+    }
+
+    /// Check whether the function has an MSI capability.
+    pub fn has_msi(&self, access: impl ConfigRegionAccess) -> bool {
+        self.has_capability(0x05, access)
+    }
+
+    /// Check whether the function has an MSI-X capability.
+    pub fn has_msix(&self, access: impl ConfigRegionAccess) -> bool {
+        self.has_capability(0x11, access)
+    }
+
+    /// Check whether the function has a PCI Express capability.
+    pub fn has_pcie(&self, access: impl ConfigRegionAccess) -> bool {
+        self.has_capability(0x10, access)
     }
 
     pub fn subsystem(&self, access: impl ConfigRegionAccess) -> (SubsystemId, SubsystemVendorId) {
@@ -298,90 +786,195 @@ impl EndpointHeader {
         (data.get_bits(16..32) as u16, data.get_bits(0..16) as u16)
     }
 
+    /// The Subsystem Vendor ID, which identifies the vendor of the board or subsystem the function is part
+    /// of. See also [`EndpointHeader::subsystem`].
+    pub fn subsystem_vendor_id(&self, access: impl ConfigRegionAccess) -> SubsystemVendorId {
+        unsafe { access.read(self.0, 0x2c) }.get_bits(0..16) as SubsystemVendorId
+    }
+
     /// Get the contents of a BAR in a given slot. Empty bars will return `None`.
     ///
     /// ### Note
     /// 64-bit memory BARs use two slots, so if one is decoded in e.g. slot #0, this method should not be called
     /// for slot #1
+    ///
+    /// Legacy memory BARs that must be mapped below 1MiB are returned as `Bar::Memory32`, with `below_1mib`
+    /// set.
     pub fn bar(&self, slot: u8, access: impl ConfigRegionAccess) -> Option<Bar> {
-        if slot >= 6 {
+        read_bar(self.0, slot, Self::MAX_BARS, access)
+    }
+
+    /// Get the contents of a BAR in a given slot, like [`EndpointHeader::bar`], but using `size_hint` as its
+    /// size if one is given, instead of probing it. This allows platforms that learn the sizes of BARs
+    /// out-of-band (e.g. from firmware tables or the Enhanced Allocation capability) to avoid writing to
+    /// live BARs. Without probing, unimplemented BARs can't be detected, so are not reported as `None`.
+    ///
+    /// Returns `None` if the hinted size does not fit in a 32-bit BAR, or the BAR uses the reserved memory
+    /// type. If `size_hint` is `None`, this behaves exactly like [`EndpointHeader::bar`].
+    pub fn bar_with_hint(&self, slot: u8, access: impl ConfigRegionAccess, size_hint: Option<u64>) -> Option<Bar> {
+        let Some(size) = size_hint else { return self.bar(slot, access) };
+        if slot >= Self::MAX_BARS as u8 {
             return None;
         }
 
         let offset = 0x10 + (slot as u16) * 4;
         let bar = unsafe { access.read(self.0, offset) };
 
-        /*
-         * If bit 0 is `0`, the BAR is in memory. If it's `1`, it's in I/O.
-         */
-        if !bar.get_bit(0) {
-            let prefetchable = bar.get_bit(3);
-            let address = bar.get_bits(4..32) << 4;
-
-            match bar.get_bits(1..3) {
-                0b00 => {
-                    let size = unsafe {
-                        access.write(self.0, offset, 0xfffffff0);
-                        let mut readback = access.read(self.0, offset);
-                        access.write(self.0, offset, address);
-
-                        /*
-                         * If the entire readback value is zero, the BAR is not implemented, so we return `None`.
-                         */
-                        if readback == 0x0 {
-                            return None;
-                        }
-
-                        readback.set_bits(0..4, 0);
-                        1 << readback.trailing_zeros()
-                    };
-                    Some(Bar::Memory32 { address, size, prefetchable })
+        if bar.get_bit(0) {
+            return Some(Bar::Io { port: bar.get_bits(2..32) << 2 });
+        }
+
+        let prefetchable = bar.get_bit(3);
+        match bar.get_bits(1..3) {
+            0b00 | 0b01 => {
+                let size = u32::try_from(size).ok()?;
+                let below_1mib = bar.get_bits(1..3) == 0b01;
+                Some(Bar::Memory32 { address: bar.get_bits(4..32) << 4, size, prefetchable, below_1mib })
+            }
+            0b10 => {
+                let (address, _) = self.bar_address(slot, access)?;
+                Some(Bar::Memory64 { address, size, prefetchable })
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the address currently programmed into the BAR in a given slot, and whether it is a 64-bit
+    /// memory BAR. Unlike [`EndpointHeader::bar`], this does not probe the size of the BAR, so never
+    /// writes to it, and is safe to use on a live device. As a consequence, it cannot detect whether the
+    /// BAR is implemented.
+    ///
+    /// Returns `None` if `slot` is out of range, or holds a 64-bit BAR with no room for its upper half.
+    pub fn bar_address(&self, slot: u8, access: impl ConfigRegionAccess) -> Option<(u64, bool)> {
+        if slot >= MAX_BARS as u8 {
+            return None;
+        }
+
+        let offset = 0x10 + (slot as u16) * 4;
+        let bar = unsafe { access.read(self.0, offset) };
+
+        if bar.get_bit(0) {
+            Some(((bar.get_bits(2..32) as u64) << 2, false))
+        } else if bar.get_bits(1..3) == 0b10 {
+            if slot as usize >= MAX_BARS - 1 {
+                return None;
+            }
+            let mut address = (bar.get_bits(4..32) as u64) << 4;
+            address.set_bits(32..64, unsafe { access.read(self.0, offset + 4) } as u64);
+            Some((address, true))
+        } else {
+            Some(((bar.get_bits(4..32) as u64) << 4, false))
+        }
+    }
+
+    /// Read the raw contents of the BAR register in a given slot, including its type bits, without
+    /// probing or decoding it. For a 64-bit BAR, the two halves are in consecutive slots.
+    ///
+    /// Returns `None` if `slot` is out of range.
+    pub fn bar_raw(&self, slot: u8, access: impl ConfigRegionAccess) -> Option<u32> {
+        if slot >= MAX_BARS as u8 {
+            return None;
+        }
+        Some(unsafe { access.read(self.0, 0x10 + (slot as u16) * 4) })
+    }
+
+    /// Get the kind of BAR in a given slot, decoded from its low bits. Like [`EndpointHeader::bar_address`],
+    /// this does not probe the size of the BAR, so is safe to use on a live device, but cannot detect
+    /// whether the BAR is implemented.
+    ///
+    /// Returns `None` if `slot` is out of range, or the BAR uses the reserved memory type.
+    pub fn bar_kind(&self, slot: u8, access: impl ConfigRegionAccess) -> Option<BarKind> {
+        if slot >= MAX_BARS as u8 {
+            return None;
+        }
+
+        let bar = unsafe { access.read(self.0, 0x10 + (slot as u16) * 4) };
+        if bar.get_bit(0) {
+            return Some(BarKind::Io);
+        }
+        match bar.get_bits(1..3) {
+            0b00 | 0b01 => Some(BarKind::Memory32),
+            0b10 => Some(BarKind::Memory64),
+            _ => None,
+        }
+    }
+
+    /// Count the BAR slots used by implemented BARs. 64-bit memory BARs use two slots, while 32-bit
+    /// memory and I/O BARs use one. Slots of unimplemented BARs are not counted.
+    ///
+    /// This decodes each BAR with [`EndpointHeader::bar`], and so probes their sizes.
+    pub fn used_bar_slots(&self, access: impl ConfigRegionAccess) -> u8 {
+        let mut used = 0;
+        let mut slot = 0;
+        while slot < MAX_BARS as u8 {
+            match self.bar(slot, &access) {
+                Some(Bar::Memory64 { .. }) => {
+                    used += 2;
+                    slot += 2;
+                }
+                Some(Bar::Memory32 { .. }) | Some(Bar::Io { .. }) => {
+                    used += 1;
+                    slot += 1;
                 }
+                None => slot += 1,
+            }
+        }
+        used
+    }
 
-                0b10 => {
-                    /*
-                     * If the BAR is 64 bit-wide and this slot is the last, there is no second slot to read.
-                     */
-                    if slot >= 5 {
-                        return None;
-                    }
+    /// Save the addresses programmed into the function's BARs, and the decode-enable bits of its Command
+    /// register, so they can be restored with [`EndpointHeader::restore_bars`] after a reset. The BARs are
+    /// read without probing their sizes, so this is safe to use on a live device.
+    pub fn save_bars(&self, access: impl ConfigRegionAccess) -> SavedBars {
+        let mut bars = [None; MAX_BARS];
+        let mut slot = 0;
+        while slot < MAX_BARS {
+            let offset = 0x10 + (slot as u16) * 4;
+            let bar = unsafe { access.read(self.0, offset) };
+
+            if bar.get_bit(0) {
+                bars[slot] = Some((bar & !0b11) as u64);
+                slot += 1;
+            } else if bar.get_bits(1..3) == 0b10 && slot < MAX_BARS - 1 {
+                /*
+                 * 64-bit BARs use the next slot for the upper half of their address, so that slot is left
+                 * as `None`.
+                 */
+                let mut address = (bar & !0b1111) as u64;
+                address.set_bits(32..64, unsafe { access.read(self.0, offset + 4) } as u64);
+                bars[slot] = Some(address);
+                slot += 2;
+            } else {
+                bars[slot] = Some((bar & !0b1111) as u64);
+                slot += 1;
+            }
+        }
+
+        let command = self.command(&access) & (CommandRegister::IO_ENABLE | CommandRegister::MEMORY_ENABLE);
+        SavedBars { bars, command }
+    }
 
-                    let address_upper = unsafe { access.read(self.0, offset + 4) };
-
-                    let size = unsafe {
-                        access.write(self.0, offset, 0xfffffff0);
-                        access.write(self.0, offset + 4, 0xffffffff);
-                        let mut readback_low = access.read(self.0, offset);
-                        let readback_high = access.read(self.0, offset + 4);
-                        access.write(self.0, offset, address);
-                        access.write(self.0, offset + 4, address_upper);
-
-                        /*
-                         * If the readback from the first slot is not 0, the size of the BAR is less than 4GiB.
-                         */
-                        readback_low.set_bits(0..4, 0);
-                        if readback_low != 0 {
-                            (1 << readback_low.trailing_zeros()) as u64
-                        } else {
-                            1u64 << ((readback_high.trailing_zeros() + 32) as u64)
-                        }
-                    };
-
-                    let address = {
-                        let mut address = address as u64;
-                        // TODO: do we need to mask off the lower bits on this?
-                        address.set_bits(32..64, address_upper as u64);
-                        address
-                    };
-
-                    Some(Bar::Memory64 { address, size, prefetchable })
+    /// Restore BAR addresses and decode-enable bits saved with [`EndpointHeader::save_bars`]. Decoding is
+    /// disabled while the BARs are written, so the function never decodes a partially-written 64-bit
+    /// address.
+    pub fn restore_bars(&mut self, access: impl ConfigRegionAccess, saved: &SavedBars) {
+        let decode = CommandRegister::IO_ENABLE | CommandRegister::MEMORY_ENABLE;
+        self.update_command(&access, |command| command - decode);
+
+        for (slot, address) in saved.bars.iter().enumerate() {
+            let Some(address) = *address else { continue };
+            let offset = 0x10 + (slot as u16) * 4;
+            let bar = unsafe { access.read(self.0, offset) };
+
+            unsafe {
+                access.write(self.0, offset, address.get_bits(0..32) as u32);
+                if !bar.get_bit(0) && bar.get_bits(1..3) == 0b10 && slot < MAX_BARS - 1 {
+                    access.write(self.0, offset + 4, address.get_bits(32..64) as u32);
                 }
-                // TODO: should we bother to return an error here?
-                _ => panic!("BAR Memory type is reserved!"),
             }
-        } else {
-            Some(Bar::Io { port: bar.get_bits(2..32) << 2 })
         }
+
+        self.update_command(&access, |command| command | (saved.command & decode));
     }
 
     /// Write to a BAR, setting the address for a device to use.
@@ -390,35 +983,15 @@ impl EndpointHeader {
     ///
     /// The supplied value must be a valid BAR value (refer to the PCIe specification for
     /// requirements) and must be of the correct size (i.e. no larger than `u32::MAX` for 32-bit
-    /// BARs). In the case of a 64-bit BAR, the supplied slot should be the first slot of the pair.
+    /// BARs). In the case of a 64-bit BAR, the supplied slot should be the first slot of the pair -
+    /// passing the second slot returns [`BarWriteError::NoSuchBar`].
     pub unsafe fn write_bar(
         &mut self,
         slot: u8,
         access: impl ConfigRegionAccess,
         value: usize,
     ) -> Result<(), BarWriteError> {
-        match self.bar(slot, &access) {
-            Some(Bar::Memory64 { .. }) => {
-                let offset = 0x10 + (slot as u16) * 4;
-                unsafe {
-                    access.write(self.0, offset, value.get_bits(0..32) as u32);
-                    access.write(self.0, offset + 4, value.get_bits(32..64) as u32);
-                }
-                Ok(())
-            }
-            Some(Bar::Memory32 { .. }) | Some(Bar::Io { .. }) => {
-                if value > u32::MAX as usize {
-                    return Err(BarWriteError::InvalidValue);
-                }
-
-                let offset = 0x10 + (slot as u16) * 4;
-                unsafe {
-                    access.write(self.0, offset, value as u32);
-                }
-                Ok(())
-            }
-            None => Err(BarWriteError::NoSuchBar),
-        }
+        unsafe { write_bar(self.0, slot, Self::MAX_BARS, access, value) }
     }
 
     pub fn interrupt(&self, access: impl ConfigRegionAccess) -> (InterruptPin, InterruptLine) {
@@ -428,6 +1001,33 @@ impl EndpointHeader {
         (data.get_bits(8..16) as u8, data.get_bits(0..8) as u8)
     }
 
+    /// Read the Interrupt Line, Interrupt Pin, Min_Gnt, and Max_Lat registers together, with a single read.
+    pub fn interrupt_full(&self, access: impl ConfigRegionAccess) -> InterruptInfo {
+        let data = unsafe { access.read(self.0, 0x3c) };
+        InterruptInfo {
+            line: data.get_bits(0..8) as InterruptLine,
+            pin: data.get_bits(8..16) as InterruptPin,
+            min_gnt: data.get_bits(16..24) as u8,
+            max_lat: data.get_bits(24..32) as u8,
+        }
+    }
+
+    /// The interrupt line the function's interrupt pin is routed to, or `None` if the line is unknown or
+    /// not connected (`0xff`).
+    pub fn interrupt_line(&self, access: impl ConfigRegionAccess) -> Option<InterruptLine> {
+        match self.interrupt(access).1 {
+            0xff => None,
+            line => Some(line),
+        }
+    }
+
+    /// The legacy interrupt pin used by the function, or `None` if it does not use a legacy interrupt pin.
+    pub fn interrupt_pin(&self, access: impl ConfigRegionAccess) -> Option<LegacyInterruptPin> {
+        LegacyInterruptPin::try_from(self.interrupt(access).0).ok()
+    }
+
+    /// Update the Interrupt Pin and Interrupt Line registers. Min_Gnt and Max_Lat, which share the
+    /// dword, are written back unchanged.
     pub fn update_interrupt<F>(&mut self, access: impl ConfigRegionAccess, f: F)
     where
         F: FnOnce((InterruptPin, InterruptLine)) -> (InterruptPin, InterruptLine),
@@ -442,6 +1042,18 @@ impl EndpointHeader {
     }
 }
 
+/// The interrupt-related registers of an endpoint, as returned by [`EndpointHeader::interrupt_full`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct InterruptInfo {
+    pub line: InterruptLine,
+    pub pin: InterruptPin,
+    /// The length of the burst period the function needs, in units of 250ns. Hardwired to `0` on PCI
+    /// Express.
+    pub min_gnt: u8,
+    /// How often the function needs to access the bus, in units of 250ns. Hardwired to `0` on PCI Express.
+    pub max_lat: u8,
+}
+
 /// PCI-PCI Bridges have a Type-1 header, so the remainder of the header is of the form:
 /// ```ignore
 ///     32                           16                              0
@@ -491,6 +1103,9 @@ impl EndpointHeader {
 pub struct PciPciBridgeHeader(PciAddress);
 
 impl PciPciBridgeHeader {
+    /// The number of BAR slots in a Type 1 header
+    pub const MAX_BARS: usize = 2;
+
     pub fn from_header(header: PciHeader, access: impl ConfigRegionAccess) -> Option<PciPciBridgeHeader> {
         match header.header_type(access) {
             HeaderType::PciPciBridge => Some(PciPciBridgeHeader(header.0)),
@@ -498,6 +1113,17 @@ impl PciPciBridgeHeader {
         }
     }
 
+    /// Create a `PciPciBridgeHeader` without checking the Header Type of the function, for callers that
+    /// have already read it.
+    ///
+    /// # Safety
+    ///
+    /// The function must have a Type-1 header. Otherwise, the methods of `PciPciBridgeHeader` will read
+    /// and write registers with a different meaning.
+    pub unsafe fn from_header_unchecked(header: PciHeader) -> PciPciBridgeHeader {
+        PciPciBridgeHeader(header.0)
+    }
+
     pub fn header(&self) -> PciHeader {
         PciHeader(self.0)
     }
@@ -506,6 +1132,10 @@ impl PciPciBridgeHeader {
         self.header().status(access)
     }
 
+    pub fn clear_status(&mut self, access: impl ConfigRegionAccess, mask: StatusRegister) {
+        self.header().clear_status(access, mask);
+    }
+
     pub fn command(&self, access: impl ConfigRegionAccess) -> CommandRegister {
         self.header().command(access)
     }
@@ -517,6 +1147,69 @@ impl PciPciBridgeHeader {
         self.header().update_command(access, f);
     }
 
+    /// Read the Secondary Status register, which reports the status of the bridge's secondary bus. It is
+    /// laid out like the primary Status register, so is interpreted through a [`StatusRegister`], with
+    /// two differences:
+    ///    - [`StatusRegister::signalled_system_error`] instead reports that the bridge detected SERR#
+    ///      asserted on the secondary bus (Received System Error)
+    ///    - [`StatusRegister::has_capability_list`] is reserved, and always `false`
+    ///
+    /// ```ignore
+    /// let status = bridge.secondary_status(&access);
+    /// if status.received_master_abort() || status.signalled_system_error() {
+    ///     // A transaction forwarded onto the secondary bus failed
+    /// }
+    /// let timing = status.devsel_timing();
+    /// ```
+    pub fn secondary_status(&self, access: impl ConfigRegionAccess) -> StatusRegister {
+        let data = unsafe { access.read(self.0, 0x1c).get_bits(16..32) };
+        StatusRegister::new(data as u16)
+    }
+
+    /// Clear the bits of the Secondary Status register that are set in `mask`. Like the primary Status
+    /// register, its error bits are write-1-to-clear, so this writes `mask` to the register while preserving
+    /// the I/O Base and I/O Limit registers, which share its dword.
+    pub fn clear_secondary_status(&mut self, access: impl ConfigRegionAccess, mask: StatusRegister) {
+        let mut data = unsafe { access.read(self.0, 0x1c) };
+        data.set_bits(16..32, mask.bits() as u32);
+        unsafe {
+            access.write(self.0, 0x1c, data);
+        }
+    }
+
+    /// Read the Subsystem ID and Subsystem Vendor ID of the bridge from its Bridge Subsystem Vendor ID
+    /// capability. Returns `None` if the bridge does not have this capability.
+    pub fn subsystem(&self, access: impl ConfigRegionAccess) -> Option<(SubsystemId, SubsystemVendorId)> {
+        let pointer = self.header().capability_pointer(&access)?;
+        let offset = capability::find_capability(self.0, pointer.into(), 0x0d, &access)?;
+        let data = unsafe { access.read(self.0, offset + 0x04) };
+        Some((data.get_bits(16..32) as u16, data.get_bits(0..16) as u16))
+    }
+
+    /// Get the contents of a BAR in a given slot. Bridges only have two BAR slots, which are often not
+    /// implemented. Empty bars will return `None`.
+    ///
+    /// ### Note
+    /// A 64-bit memory BAR uses both slots, so if one is decoded in slot #0, this method should not be
+    /// called for slot #1
+    pub fn bar(&self, slot: u8, access: impl ConfigRegionAccess) -> Option<Bar> {
+        read_bar(self.0, slot, Self::MAX_BARS, access)
+    }
+
+    /// Write to a BAR, setting the address for the bridge to use.
+    ///
+    /// # Safety
+    ///
+    /// The same requirements as [`EndpointHeader::write_bar`] apply.
+    pub unsafe fn write_bar(
+        &mut self,
+        slot: u8,
+        access: impl ConfigRegionAccess,
+        value: usize,
+    ) -> Result<(), BarWriteError> {
+        unsafe { write_bar(self.0, slot, Self::MAX_BARS, access, value) }
+    }
+
     pub fn primary_bus_number(&self, access: impl ConfigRegionAccess) -> u8 {
         let data = unsafe { access.read(self.0, 0x18).get_bits(0..8) };
         data as u8
@@ -532,6 +1225,88 @@ impl PciPciBridgeHeader {
         data as u8
     }
 
+    /// The buses behind the bridge, from its secondary bus number to its subordinate bus number (inclusive).
+    /// The range is empty if the subordinate bus number is below the secondary bus number.
+    pub fn subordinate_bus_range(&self, access: impl ConfigRegionAccess) -> RangeInclusive<u8> {
+        let data = unsafe { access.read(self.0, 0x18) };
+        (data.get_bits(8..16) as u8)..=(data.get_bits(16..24) as u8)
+    }
+
+    /// The Secondary Latency Timer, in units of PCI bus clocks of the secondary bus. Hardwired to `0` on
+    /// PCI Express.
+    pub fn secondary_latency_timer(&self, access: impl ConfigRegionAccess) -> u8 {
+        unsafe { access.read(self.0, 0x18) }.get_bits(24..32) as u8
+    }
+
+    pub fn set_secondary_latency_timer(&mut self, timer: u8, access: impl ConfigRegionAccess) {
+        let mut data = unsafe { access.read(self.0, 0x18) };
+        data.set_bits(24..32, timer as u32);
+        unsafe {
+            access.write(self.0, 0x18, data);
+        }
+    }
+
+    /// Returns `true` if the bridge forwards configuration transactions to `address` - that is, if
+    /// `address` is in the same segment as the bridge, and on a bus between the bridge's secondary and
+    /// subordinate bus numbers (inclusive).
+    pub fn decodes(&self, address: PciAddress, access: impl ConfigRegionAccess) -> bool {
+        address.segment() == self.0.segment() && self.subordinate_bus_range(access).contains(&address.bus())
+    }
+
+    /// Read the address windows through which the bridge forwards transactions from its primary bus to
+    /// its secondary bus. A window is `None` if it is disabled (its base is above its limit).
+    ///
+    /// Bridges that do not implement the I/O or prefetchable memory windows hardwire the base and limit
+    /// registers to `0`, which decodes as a window from `0` to the window's granularity.
+    pub fn windows(&self, access: impl ConfigRegionAccess) -> BridgeWindows {
+        /*
+         * I/O windows have a granularity of 4KiB. The lower 16 bits of the base and limit are encoded in
+         * the I/O Base and I/O Limit registers, and if the bridge supports 32-bit I/O addressing, the upper
+         * 16 bits are held at `0x30`.
+         */
+        let io = unsafe { access.read(self.0, 0x1c) };
+        let io_32bit = io.get_bits(0..4) == 0x1;
+        let (io_base_upper, io_limit_upper) = if io_32bit {
+            let upper = unsafe { access.read(self.0, 0x30) };
+            (upper.get_bits(0..16), upper.get_bits(16..32))
+        } else {
+            (0, 0)
+        };
+        let io_base = (io_base_upper << 16) | (io.get_bits(4..8) << 12);
+        let io_limit = (io_limit_upper << 16) | (io.get_bits(12..16) << 12) | 0xfff;
+
+        /*
+         * Memory windows have a granularity of 1MiB, and are always 32-bit.
+         */
+        let memory = unsafe { access.read(self.0, 0x20) };
+        let memory_base = memory.get_bits(4..16) << 20;
+        let memory_limit = (memory.get_bits(20..32) << 20) | 0xfffff;
+
+        /*
+         * Prefetchable memory windows also have a granularity of 1MiB, but the bridge may support 64-bit
+         * addresses, in which case the upper 32 bits are held at `0x28` and `0x2c`.
+         */
+        let prefetchable = unsafe { access.read(self.0, 0x24) };
+        let prefetchable_64bit = prefetchable.get_bits(0..4) == 0x1;
+        let (prefetchable_base_upper, prefetchable_limit_upper) = if prefetchable_64bit {
+            unsafe { (access.read(self.0, 0x28) as u64, access.read(self.0, 0x2c) as u64) }
+        } else {
+            (0, 0)
+        };
+        let prefetchable_base = (prefetchable_base_upper << 32) | ((prefetchable.get_bits(4..16) as u64) << 20);
+        let prefetchable_limit =
+            (prefetchable_limit_upper << 32) | ((prefetchable.get_bits(20..32) as u64) << 20) | 0xfffff;
+
+        BridgeWindows {
+            io: (io_base <= io_limit).then_some(io_base..=io_limit),
+            io_32bit,
+            memory: (memory_base <= memory_limit).then_some(memory_base..=memory_limit),
+            prefetchable: (prefetchable_base <= prefetchable_limit)
+                .then_some(prefetchable_base..=prefetchable_limit),
+            prefetchable_64bit,
+        }
+    }
+
     pub fn update_bus_number<F>(&self, access: impl ConfigRegionAccess, f: F)
     where
         F: FnOnce(BusNumber) -> BusNumber,
@@ -549,6 +1324,75 @@ impl PciPciBridgeHeader {
             access.write(self.0, 0x18, data);
         }
     }
+
+    pub fn bridge_control(&self, access: impl ConfigRegionAccess) -> BridgeControl {
+        let data = unsafe { access.read(self.0, 0x3c).get_bits(16..32) };
+        BridgeControl::from_bits_retain(data as u16)
+    }
+
+    /// Update the Bridge Control register. The Discard Timer Status bit is write-1-to-clear, so it is never
+    /// written back, to avoid clearing it unintentionally.
+    pub fn update_bridge_control<F>(&mut self, access: impl ConfigRegionAccess, f: F)
+    where
+        F: FnOnce(BridgeControl) -> BridgeControl,
+    {
+        let mut data = unsafe { access.read(self.0, 0x3c) };
+        let new_control =
+            f(BridgeControl::from_bits_retain(data.get_bits(16..32) as u16)) - BridgeControl::DISCARD_TIMER_STATUS;
+        data.set_bits(16..32, new_control.bits() as u32);
+        unsafe {
+            access.write(self.0, 0x3c, data);
+        }
+    }
+
+    pub fn interrupt(&self, access: impl ConfigRegionAccess) -> (InterruptPin, InterruptLine) {
+        let data = unsafe { access.read(self.0, 0x3c) };
+        (data.get_bits(8..16) as u8, data.get_bits(0..8) as u8)
+    }
+
+    /// Update the Interrupt Pin and Interrupt Line registers. The Bridge Control register shares the dword,
+    /// and is written back unchanged, except that the write-1-to-clear Discard Timer Status bit is written
+    /// as `0` to avoid clearing it unintentionally.
+    pub fn update_interrupt<F>(&mut self, access: impl ConfigRegionAccess, f: F)
+    where
+        F: FnOnce((InterruptPin, InterruptLine)) -> (InterruptPin, InterruptLine),
+    {
+        let mut data = unsafe { access.read(self.0, 0x3c) };
+        let (new_pin, new_line) = f((data.get_bits(8..16) as u8, data.get_bits(0..8) as u8));
+        data.set_bits(8..16, new_pin.into());
+        data.set_bits(0..8, new_line.into());
+        // Discard Timer Status is bit 10 of the Bridge Control register
+        data.set_bit(26, false);
+        unsafe {
+            access.write(self.0, 0x3c, data);
+        }
+    }
+
+    /// Assert reset on the bridge's secondary bus, by setting the Secondary Bus Reset bit. Reset stays
+    /// asserted until [`PciPciBridgeHeader::clear_secondary_bus_reset`] is called, which must not happen
+    /// for at least 1ms to meet the minimum reset duration required by the specification. This crate does
+    /// not perform the delay itself.
+    pub fn secondary_bus_reset(&mut self, access: impl ConfigRegionAccess) {
+        self.update_bridge_control(access, |control| control | BridgeControl::SECONDARY_BUS_RESET);
+    }
+
+    /// Deassert reset on the bridge's secondary bus.
+    pub fn clear_secondary_bus_reset(&mut self, access: impl ConfigRegionAccess) {
+        self.update_bridge_control(access, |control| control - BridgeControl::SECONDARY_BUS_RESET);
+    }
+}
+
+/// The address windows of a PCI-PCI bridge, as returned by [`PciPciBridgeHeader::windows`]. Windows are
+/// inclusive ranges, as the limit of a window may be the highest address in the address space.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BridgeWindows {
+    pub io: Option<RangeInclusive<u32>>,
+    /// Whether the bridge supports 32-bit I/O addresses. If not, the I/O window is limited to 16 bits.
+    pub io_32bit: bool,
+    pub memory: Option<RangeInclusive<u32>>,
+    pub prefetchable: Option<RangeInclusive<u64>>,
+    /// Whether the bridge supports 64-bit prefetchable memory addresses
+    pub prefetchable_64bit: bool,
 }
 
 pub struct BusNumber {
@@ -557,11 +1401,171 @@ pub struct BusNumber {
     pub subordinate: u8,
 }
 
+/// Decode the BAR in `slot` of the function at `address`, probing its size. Shared between header types,
+/// which differ only in how many BAR slots they have.
+fn read_bar(address: PciAddress, slot: u8, max_bars: usize, access: impl ConfigRegionAccess) -> Option<Bar> {
+    if slot as usize >= max_bars {
+        return None;
+    }
+
+    let offset = 0x10 + (slot as u16) * 4;
+    let bar = unsafe { access.read(address, offset) };
+
+    /*
+     * If bit 0 is `0`, the BAR is in memory. If it's `1`, it's in I/O.
+     */
+    if !bar.get_bit(0) {
+        let prefetchable = bar.get_bit(3);
+        let bar_address = bar.get_bits(4..32) << 4;
+
+        match bar.get_bits(1..3) {
+            /*
+             * Type `0b01` is a legacy encoding for 32-bit BARs that must be mapped below 1MiB. It
+             * is sized and decoded in the same way as a normal 32-bit BAR, but the constraint is
+             * reported through `below_1mib`.
+             */
+            0b00 | 0b01 => {
+                let size = unsafe {
+                    access.write(address, offset, 0xfffffff0);
+                    let mut readback = access.read(address, offset);
+                    access.write(address, offset, bar_address);
+
+                    /*
+                     * If the entire readback value is zero, the BAR is not implemented, so we return `None`.
+                     */
+                    if readback == 0x0 {
+                        return None;
+                    }
+
+                    readback.set_bits(0..4, 0);
+                    1 << readback.trailing_zeros()
+                };
+                Some(Bar::Memory32 {
+                    address: bar_address,
+                    size,
+                    prefetchable,
+                    below_1mib: bar.get_bits(1..3) == 0b01,
+                })
+            }
+
+            0b10 => {
+                /*
+                 * If the BAR is 64 bit-wide and this slot is the last, there is no second slot to read.
+                 */
+                if slot as usize + 1 >= max_bars {
+                    return None;
+                }
+
+                let address_upper = unsafe { access.read(address, offset + 4) };
+
+                let size = unsafe {
+                    access.write(address, offset, 0xfffffff0);
+                    access.write(address, offset + 4, 0xffffffff);
+                    let mut readback_low = access.read(address, offset);
+                    let readback_high = access.read(address, offset + 4);
+                    access.write(address, offset, bar_address);
+                    access.write(address, offset + 4, address_upper);
+
+                    /*
+                     * If the readback from the first slot is not 0, the size of the BAR is less than 4GiB.
+                     */
+                    readback_low.set_bits(0..4, 0);
+                    if readback_low != 0 {
+                        (1 << readback_low.trailing_zeros()) as u64
+                    } else {
+                        1u64 << ((readback_high.trailing_zeros() + 32) as u64)
+                    }
+                };
+
+                let bar_address = {
+                    let mut bar_address = bar_address as u64;
+                    // TODO: do we need to mask off the lower bits on this?
+                    bar_address.set_bits(32..64, address_upper as u64);
+                    bar_address
+                };
+
+                Some(Bar::Memory64 { address: bar_address, size, prefetchable })
+            }
+            // TODO: should we bother to return an error here?
+            _ => panic!("BAR Memory type is reserved!"),
+        }
+    } else {
+        Some(Bar::Io { port: bar.get_bits(2..32) << 2 })
+    }
+}
+
+/// Write `value` to the BAR in `slot` of the function at `address`. Shared between header types.
+///
+/// # Safety
+///
+/// The same requirements as [`EndpointHeader::write_bar`] apply.
+unsafe fn write_bar(
+    address: PciAddress,
+    slot: u8,
+    max_bars: usize,
+    access: impl ConfigRegionAccess,
+    value: usize,
+) -> Result<(), BarWriteError> {
+    if slot as usize >= max_bars || is_upper_half_of_64bit_bar(address, slot, max_bars, &access) {
+        return Err(BarWriteError::NoSuchBar);
+    }
+
+    match read_bar(address, slot, max_bars, &access) {
+        Some(Bar::Memory64 { .. }) => {
+            let offset = 0x10 + (slot as u16) * 4;
+            unsafe {
+                access.write(address, offset, value.get_bits(0..32) as u32);
+                access.write(address, offset + 4, value.get_bits(32..64) as u32);
+            }
+            Ok(())
+        }
+        Some(Bar::Memory32 { .. }) | Some(Bar::Io { .. }) => {
+            if value > u32::MAX as usize {
+                return Err(BarWriteError::InvalidValue);
+            }
+
+            let offset = 0x10 + (slot as u16) * 4;
+            unsafe {
+                access.write(address, offset, value as u32);
+            }
+            Ok(())
+        }
+        None => Err(BarWriteError::NoSuchBar),
+    }
+}
+
+/// Check whether `slot` holds the upper half of a 64-bit BAR's address, rather than a BAR of its own.
+/// The contents of the upper half can look like any type of BAR, so this walks the BARs from the first
+/// slot, without probing their sizes.
+fn is_upper_half_of_64bit_bar(
+    address: PciAddress,
+    slot: u8,
+    max_bars: usize,
+    access: impl ConfigRegionAccess,
+) -> bool {
+    let mut current = 0;
+    while current < slot && (current as usize) < max_bars {
+        let bar = unsafe { access.read(address, 0x10 + (current as u16) * 4) };
+        if !bar.get_bit(0) && bar.get_bits(1..3) == 0b10 {
+            if current + 1 == slot {
+                return true;
+            }
+            current += 2;
+        } else {
+            current += 1;
+        }
+    }
+    false
+}
+
+/// The number of BAR slots of an endpoint. Bridges have fewer - see [`PciPciBridgeHeader::MAX_BARS`].
 pub const MAX_BARS: usize = 6;
 
+/// A decoded BAR. A `Memory32` BAR with `below_1mib` set uses the legacy encoding for BARs that must be
+/// mapped below 1MiB, and must not be reprogrammed to an address above that.
 #[derive(Clone, Copy, Debug)]
 pub enum Bar {
-    Memory32 { address: u32, size: u32, prefetchable: bool },
+    Memory32 { address: u32, size: u32, prefetchable: bool, below_1mib: bool },
     Memory64 { address: u64, size: u64, prefetchable: bool },
     Io { port: u32 },
 }
@@ -578,7 +1582,7 @@ impl Bar {
     /// Return the address and size of this BAR or panic if not a memory BAR.
     pub fn unwrap_mem(self) -> (usize, usize) {
         match self {
-            Bar::Memory32 { address, size, prefetchable: _ } => (address as usize, size as usize),
+            Bar::Memory32 { address, size, prefetchable: _, below_1mib: _ } => (address as usize, size as usize),
             Bar::Memory64 { address, size, prefetchable: _ } => (
                 address.try_into().expect("conversion from 64bit BAR to usize failed"),
                 size.try_into().expect("conversion from 64bit BAR to usize failed"),
@@ -588,6 +1592,36 @@ impl Bar {
     }
 }
 
+/// The kind of a BAR, as returned by [`EndpointHeader::bar_kind`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BarKind {
+    /// A 32-bit memory BAR, which uses one slot
+    Memory32,
+    /// A 64-bit memory BAR, which uses two slots
+    Memory64,
+    /// An I/O BAR, which uses one slot
+    Io,
+}
+
+impl BarKind {
+    /// The number of BAR slots a BAR of this kind uses
+    pub fn slots(&self) -> u8 {
+        match self {
+            BarKind::Memory64 => 2,
+            BarKind::Memory32 | BarKind::Io => 1,
+        }
+    }
+}
+
+/// The state of a function's BARs, saved by [`EndpointHeader::save_bars`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SavedBars {
+    /// The address programmed into each BAR. Slots holding the upper half of a 64-bit BAR are `None`.
+    pub bars: [Option<u64>; MAX_BARS],
+    /// The decode-enable bits of the Command register
+    pub command: CommandRegister,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum BarWriteError {
     NoSuchBar,
@@ -45,6 +45,11 @@ impl StatusRegister {
         StatusRegister(value)
     }
 
+    /// The raw value of the register, including reserved bits.
+    pub fn bits(&self) -> u16 {
+        self.0
+    }
+
     /// Will be `true` whenever the device detects a parity error, even if parity error handling is disabled.
     pub fn parity_error_detected(&self) -> bool {
         self.0.get_bit(15)
@@ -111,6 +116,12 @@ impl StatusRegister {
         self.0.get_bit(4)
     }
 
+    /// A view of the register that only includes the bits that are meaningful on PCI Express, omitting
+    /// those that are hardwired for PCI Express functions from its `Debug` output.
+    pub fn pcie_view(&self) -> PcieStatusView {
+        PcieStatusView(*self)
+    }
+
     /// Represents the state of the device's INTx# signal. If returns `true` and bit 10 of the
     /// Command register (Interrupt Disable bit) is set to 0 the signal will be asserted;
     /// otherwise, the signal will be ignored.
@@ -137,6 +148,34 @@ impl Debug for StatusRegister {
     }
 }
 
+/// A [`StatusRegister`] of a PCI Express function, as returned by [`StatusRegister::pcie_view`]. Its
+/// `Debug` output omits DEVSEL timing, fast back-to-back capability, and 66MHz capability, which are
+/// hardwired on PCI Express.
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub struct PcieStatusView(StatusRegister);
+
+impl PcieStatusView {
+    /// The full register
+    pub fn register(&self) -> StatusRegister {
+        self.0
+    }
+}
+
+impl Debug for PcieStatusView {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PcieStatusView")
+            .field("parity_error_detected", &self.0.parity_error_detected())
+            .field("signalled_system_error", &self.0.signalled_system_error())
+            .field("received_master_abort", &self.0.received_master_abort())
+            .field("received_target_abort", &self.0.received_target_abort())
+            .field("signalled_target_abort", &self.0.signalled_target_abort())
+            .field("master_data_parity_error", &self.0.master_data_parity_error())
+            .field("has_capability_list", &self.0.has_capability_list())
+            .field("interrupt_status", &self.0.interrupt_status())
+            .finish()
+    }
+}
+
 bitflags::bitflags! {
     #[repr(transparent)]
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -155,3 +194,25 @@ bitflags::bitflags! {
         const _ = !0;
     }
 }
+
+bitflags::bitflags! {
+    /// The Bridge Control register of a PCI-PCI bridge.
+    #[repr(transparent)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct BridgeControl: u16 {
+        const PARITY_ERROR_RESPONSE_ENABLE = 1 << 0;
+        const SERR_ENABLE = 1 << 1;
+        const ISA_ENABLE = 1 << 2;
+        const VGA_ENABLE = 1 << 3;
+        const VGA_16_BIT_DECODE = 1 << 4;
+        const MASTER_ABORT_MODE = 1 << 5;
+        const SECONDARY_BUS_RESET = 1 << 6;
+        const FAST_BACK_TO_BACK_ENABLE = 1 << 7;
+        const PRIMARY_DISCARD_TIMEOUT = 1 << 8;
+        const SECONDARY_DISCARD_TIMEOUT = 1 << 9;
+        /// Write-1-to-clear
+        const DISCARD_TIMER_STATUS = 1 << 10;
+        const DISCARD_TIMER_SERR_ENABLE = 1 << 11;
+        const _ = !0;
+    }
+}
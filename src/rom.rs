@@ -0,0 +1,27 @@
+//! Helpers for interpreting the contents of an Expansion ROM, once it has been enabled and mapped by the
+//! caller.
+//!
+//! An Expansion ROM contains one or more images, each starting with a header holding the signature
+//! `0x55, 0xAA` and the length of the image in 512-byte units.
+
+/// The signature at the start of every Expansion ROM image
+pub const ROM_SIGNATURE: [u8; 2] = [0x55, 0xaa];
+
+/// Check whether the Expansion ROM image at `rom_base` starts with a valid signature.
+///
+/// # Safety
+///
+/// `rom_base` must point to at least 2 bytes of readable memory, such as a mapped Expansion ROM.
+pub unsafe fn rom_image_valid(rom_base: *const u8) -> bool {
+    rom_base.read_volatile() == ROM_SIGNATURE[0] && rom_base.add(1).read_volatile() == ROM_SIGNATURE[1]
+}
+
+/// Get the size, in bytes, of the Expansion ROM image at `rom_base`, as recorded in its header. This is
+/// only meaningful if [`rom_image_valid`] returns `true` for the image.
+///
+/// # Safety
+///
+/// `rom_base` must point to at least 3 bytes of readable memory, such as a mapped Expansion ROM.
+pub unsafe fn rom_image_size(rom_base: *const u8) -> usize {
+    rom_base.add(2).read_volatile() as usize * 512
+}
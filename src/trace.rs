@@ -0,0 +1,58 @@
+use crate::{ConfigRegionAccess, PciAddress};
+use core::cell::RefCell;
+
+/// A configuration space access observed by [`TracingAccess`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TraceEvent {
+    /// A read, which returned `value`
+    Read { value: u32 },
+    /// A write of `value`
+    Write { value: u32 },
+}
+
+/// A [`ConfigRegionAccess`] that wraps another, and calls a callback with every read and write made
+/// through it. This is useful for tracing the configuration space accesses made during enumeration or
+/// by a driver, without modifying the underlying accessor.
+///
+/// The callback is called after each read, so it can observe the value returned, and before each
+/// write. It must not access configuration space through the same `TracingAccess`.
+pub struct TracingAccess<A, F>
+where
+    A: ConfigRegionAccess,
+    F: FnMut(PciAddress, u16, TraceEvent),
+{
+    inner: A,
+    callback: RefCell<F>,
+}
+
+impl<A, F> TracingAccess<A, F>
+where
+    A: ConfigRegionAccess,
+    F: FnMut(PciAddress, u16, TraceEvent),
+{
+    pub fn new(inner: A, callback: F) -> TracingAccess<A, F> {
+        TracingAccess { inner, callback: RefCell::new(callback) }
+    }
+
+    /// Unwrap the underlying accessor
+    pub fn into_inner(self) -> A {
+        self.inner
+    }
+}
+
+impl<A, F> ConfigRegionAccess for TracingAccess<A, F>
+where
+    A: ConfigRegionAccess,
+    F: FnMut(PciAddress, u16, TraceEvent),
+{
+    unsafe fn read(&self, address: PciAddress, offset: u16) -> u32 {
+        let value = self.inner.read(address, offset);
+        (self.callback.borrow_mut())(address, offset, TraceEvent::Read { value });
+        value
+    }
+
+    unsafe fn write(&self, address: PciAddress, offset: u16, value: u32) {
+        (self.callback.borrow_mut())(address, offset, TraceEvent::Write { value });
+        self.inner.write(address, offset, value)
+    }
+}